@@ -1,11 +1,18 @@
+use crate::asset_pattern::{AssetMatcher, ExcludeFilter, glob_to_regex};
 use crate::client::RequestClient;
 use crate::compare_release_version::Compare;
+use crate::download::DownloadOptions;
 use crate::error::{GrabError, Result};
 use crate::github_release;
-use crate::github_version::Version;
-use crate::models::App;
+use crate::github_version::{Discovery, Version};
+use crate::install::{self, InstallOptions, Uninstalled};
+use crate::models::{App, InstallRecord};
+use crate::output::safe_file_name;
+use crate::platform::Platform;
 use crate::remote;
+use crate::signature;
 use crate::storage;
+use regex::Regex;
 use std::process::Output;
 use tokio::process::Command;
 
@@ -29,47 +36,90 @@ async fn command(name: &str, flag: &str) -> Result<Output> {
 }
 
 pub fn add_app(app: App) -> Result<()> {
+    safe_file_name(&app.name)?;
+    safe_file_name(app.bin_name())?;
+    ExcludeFilter::new(&[], &app.exclude)?;
+    if let Some(pattern) = &app.asset_pattern {
+        AssetMatcher::for_release(app.pattern_kind, pattern, "v0.0.0", &Platform::detect())?;
+    }
+    if let Some(key) = &app.minisign_key {
+        signature::validate_minisign_key(key)?;
+    }
+    if let Some(key) = &app.ssh_key {
+        signature::validate_ssh_key(key)?;
+    }
+    let install_globs = app.install.iter().map(|rule| rule.source.as_str());
+    for glob in app.bin_path.as_deref().into_iter().chain(install_globs) {
+        Regex::new(&glob_to_regex(glob))?;
+    }
+
     let mut apps = storage::load_apps()?;
     apps.push(app);
     storage::save_apps(&apps)
 }
 
-pub async fn check_apps(download: bool) -> Result<()> {
+#[derive(Debug, Clone)]
+pub enum UpdateAction {
+    Report,
+    Download,
+    Install(InstallOptions),
+}
+
+pub async fn check_apps(
+    action: UpdateAction,
+    discovery: Discovery,
+    options: DownloadOptions,
+) -> Result<()> {
     let client = RequestClient::new()?;
     let apps = storage::load_apps()?;
     let mut tasks = tokio::task::JoinSet::new();
 
     for app in apps {
         let client = client.clone();
+        let options = options.clone();
+        let action = action.clone();
 
         tasks.spawn(async move {
-            let version_output = command(&app.name, &app.version_flag).await?;
-            let latest_version = Version::latest(&app, &client).await?;
-            let local_version = String::from_utf8_lossy(&version_output.stdout)
-                .trim()
-                .to_string();
+            let _job = options.acquire_job().await?;
+            let local_version = match &app.active_version {
+                Some(version) => version.clone(),
+                None => {
+                    let version_output = command(app.bin_name(), &app.version_flag).await?;
+                    String::from_utf8_lossy(&version_output.stdout)
+                        .trim()
+                        .to_string()
+                }
+            };
+            let latest_version = Version::discover(&app, &client, discovery).await?;
 
             let compare = Compare::new(&latest_version.tag_name, &local_version)?;
 
-            println!(
+            options.progress.println(format!(
                 "Local version of {}: {}\nLatest version in GitHub repo: {}\nURL: {}\n",
                 app.name, local_version, latest_version.tag_name, latest_version.html_url
-            );
-
-            if !compare.is_latest && download {
-                println!(
-                    "Update available for {} — downloading latest release...\n",
-                    app.name
-                );
-                github_release::download_latest_asset(
-                    &client,
-                    &app.owner,
-                    &app.repo,
-                    &app.asset_pattern,
-                )
-                .await?;
+            ));
+
+            if !compare.is_latest && !matches!(action, UpdateAction::Report) {
+                match &action {
+                    UpdateAction::Install(install) => {
+                        options.progress.println(format!(
+                            "Update available for {} — installing latest release...\n",
+                            app.name
+                        ));
+                        install::install_latest(&client, &app, install, &options).await?;
+                    }
+                    _ => {
+                        options.progress.println(format!(
+                            "Update available for {} — downloading latest release...\n",
+                            app.name
+                        ));
+                        github_release::download_latest_asset(&client, &app, &options).await?;
+                    }
+                }
             } else {
-                println!("{} is already up to date.\n", app.name);
+                options
+                    .progress
+                    .println(format!("{} is already up to date.\n", app.name));
             }
 
             Ok::<(), GrabError>(())
@@ -80,10 +130,11 @@ pub async fn check_apps(download: bool) -> Result<()> {
         result.map_err(GrabError::TaskJoin)??;
     }
 
+    options.progress.finish();
     Ok(())
 }
 
-pub async fn download_apps() -> Result<()> {
+pub async fn download_apps(options: DownloadOptions) -> Result<()> {
     let client = RequestClient::new()?;
 
     let apps = storage::load_apps()?;
@@ -92,14 +143,12 @@ pub async fn download_apps() -> Result<()> {
 
     for app in apps {
         let client = client.clone();
+        let options = options.clone();
         tasks.spawn(async move {
-            github_release::download_latest_asset(
-                &client,
-                &app.owner,
-                &app.repo,
-                &app.asset_pattern,
-            )
-            .await
+            let _job = options.acquire_job().await?;
+            github_release::download_latest_asset(&client, &app, &options)
+                .await
+                .map(|_| ())
         });
     }
 
@@ -107,9 +156,101 @@ pub async fn download_apps() -> Result<()> {
         result.map_err(GrabError::TaskJoin)??;
     }
 
+    options.progress.finish();
     Ok(())
 }
 
+pub async fn install_apps(
+    names: Vec<String>,
+    install: InstallOptions,
+    options: DownloadOptions,
+) -> Result<()> {
+    let client = RequestClient::new()?;
+    let apps = select_apps(storage::load_apps()?, &names)?;
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for app in apps {
+        let client = client.clone();
+        let install = install.clone();
+        let options = options.clone();
+        tasks.spawn(async move {
+            let _job = options.acquire_job().await?;
+            install::install_latest(&client, &app, &install, &options)
+                .await
+                .map(|_| ())
+        });
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        result.map_err(GrabError::TaskJoin)??;
+    }
+
+    options.progress.finish();
+    Ok(())
+}
+
+fn select_apps(apps: Vec<App>, names: &[String]) -> Result<Vec<App>> {
+    if let Some(missing) = names
+        .iter()
+        .find(|name| !apps.iter().any(|app| &app.name == *name))
+    {
+        return Err(GrabError::NotFound(format!("No app named '{missing}'")));
+    }
+
+    Ok(apps
+        .into_iter()
+        .filter(|app| names.is_empty() || names.contains(&app.name))
+        .collect())
+}
+
+pub fn rollback_app(
+    name: &str,
+    version: Option<&str>,
+    install: &InstallOptions,
+) -> Result<(InstallRecord, InstallRecord)> {
+    let app = find_app(name)?;
+    let active = app.active_install().cloned().ok_or_else(|| {
+        GrabError::NotFound(format!("{} has not been installed with grab", app.name))
+    })?;
+
+    let record = install::rollback(&app, version, install)?;
+    storage::modify_app(&app, |stored| {
+        stored.active_version = Some(record.version.clone());
+    })?;
+
+    Ok((active, record))
+}
+
+pub fn uninstall_app(name: &str, force: bool) -> Result<Uninstalled> {
+    let app = find_app(name)?;
+    if app.installs.is_empty() {
+        return Err(GrabError::NotFound(format!(
+            "{} has not been installed with grab",
+            app.name
+        )));
+    }
+
+    let result = install::uninstall(&app, force)?;
+    storage::modify_app(&app, |stored| {
+        stored.installs.retain(|record| {
+            result
+                .modified
+                .iter()
+                .any(|path| path.starts_with(&record.dir))
+        });
+        stored.active_version = None;
+    })?;
+
+    Ok(result)
+}
+
+fn find_app(name: &str) -> Result<App> {
+    storage::load_apps()?
+        .into_iter()
+        .find(|app| app.name == name)
+        .ok_or_else(|| GrabError::NotFound(format!("No app named '{name}'")))
+}
+
 pub fn remove_app(index: usize) -> Result<()> {
     if index == 0 {
         return Err(GrabError::InvalidInput(
@@ -163,7 +304,7 @@ pub fn list_apps() -> Result<Vec<(usize, App)>> {
     Ok(results)
 }
 
-pub(crate) async fn download_remote_apps(file: String) -> Result<()> {
+pub(crate) async fn download_remote_apps(file: String, options: DownloadOptions) -> Result<()> {
     let client = RequestClient::new()?;
 
     let apps = remote::load_apps(&client, &file).await?;
@@ -171,14 +312,12 @@ pub(crate) async fn download_remote_apps(file: String) -> Result<()> {
 
     for app in apps {
         let client = client.clone();
+        let options = options.clone();
         tasks.spawn(async move {
-            github_release::download_latest_asset(
-                &client,
-                &app.owner,
-                &app.repo,
-                &app.asset_pattern,
-            )
-            .await
+            let _job = options.acquire_job().await?;
+            github_release::download_latest_asset(&client, &app, &options)
+                .await
+                .map(|_| ())
         });
     }
 
@@ -186,5 +325,6 @@ pub(crate) async fn download_remote_apps(file: String) -> Result<()> {
         result.map_err(GrabError::TaskJoin)??;
     }
 
+    options.progress.finish();
     Ok(())
 }
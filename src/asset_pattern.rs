@@ -0,0 +1,399 @@
+use crate::asset_score::{CHECKSUM_MARKERS, CHECKSUM_SUFFIXES};
+use crate::error::{GrabError, Result};
+use crate::models::PatternKind;
+use crate::platform::Platform;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{(version|tag|os|arch|goos|goarch|target)\}")
+        .expect("Invalid regex check the placeholder pattern syntax.")
+});
+
+pub fn default_excludes() -> Vec<String> {
+    let suffixes = CHECKSUM_SUFFIXES.iter().map(|suffix| format!("*{suffix}"));
+    let markers = CHECKSUM_MARKERS.iter().map(|marker| format!("*{marker}*"));
+    suffixes.chain(markers).collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct ExcludeFilter {
+    patterns: Vec<Regex>,
+}
+
+impl ExcludeFilter {
+    pub fn new(defaults: &[String], excludes: &[String]) -> Result<Self> {
+        let patterns = defaults
+            .iter()
+            .chain(excludes)
+            .map(|glob| {
+                Regex::new(&format!("(?i){}", glob_to_regex(glob))).map_err(|e| {
+                    GrabError::InvalidInput(format!("Invalid exclude pattern '{glob}': {e}"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    pub fn is_excluded(&self, name: &str) -> bool {
+        self.patterns.iter().any(|pattern| pattern.is_match(name))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum AssetMatcher {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl AssetMatcher {
+    pub fn new(kind: PatternKind, pattern: &str) -> Result<Self> {
+        let source = match kind {
+            PatternKind::Substring => return Ok(AssetMatcher::Substring(pattern.to_string())),
+            PatternKind::Glob => glob_to_regex(pattern),
+            PatternKind::Regex => format!("^(?:{pattern})$"),
+        };
+
+        Regex::new(&source).map(AssetMatcher::Regex).map_err(|e| {
+            GrabError::InvalidInput(format!("Invalid {kind} asset pattern '{pattern}': {e}"))
+        })
+    }
+
+    pub fn for_release(
+        kind: PatternKind,
+        pattern: &str,
+        tag: &str,
+        platform: &Platform,
+    ) -> Result<Self> {
+        Self::new(kind, &expand_template(pattern, kind, tag, platform))
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        match self {
+            AssetMatcher::Substring(pattern) => name.contains(pattern.as_str()),
+            AssetMatcher::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+pub fn version_from_tag(tag: &str) -> &str {
+    tag.trim_start_matches(|c: char| !c.is_ascii_digit())
+}
+
+pub fn expand_template(pattern: &str, kind: PatternKind, tag: &str, platform: &Platform) -> String {
+    PLACEHOLDER_RE
+        .replace_all(pattern, |caps: &Captures| {
+            let value = match &caps[1] {
+                "version" => version_from_tag(tag).to_string(),
+                "tag" => tag.to_string(),
+                "os" => platform.os.to_string(),
+                "arch" => platform.arch.to_string(),
+                "goos" => platform.os.go_name(),
+                "goarch" => platform.arch.go_name(),
+                _ => platform.target_triple(),
+            };
+
+            match kind {
+                PatternKind::Substring => value,
+                PatternKind::Glob => escape_glob(&value),
+                PatternKind::Regex => regex::escape(&value),
+            }
+        })
+        .into_owned()
+}
+
+fn escape_glob(value: &str) -> String {
+    value
+        .chars()
+        .fold(String::with_capacity(value.len()), |mut escaped, c| {
+            if matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | ',' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        })
+}
+
+const VERSION_REGEX: &str = r"\d+(?:\.\d+)*";
+
+pub fn tightened_pattern(chosen: &str, tag: &str, candidates: &[&str]) -> String {
+    let exact = regex::escape(chosen);
+    let version = version_from_tag(tag);
+    if version.is_empty() {
+        return exact;
+    }
+
+    let generalized = exact.replace(&regex::escape(version), VERSION_REGEX);
+    match Regex::new(&format!("^(?:{generalized})$")) {
+        Ok(regex)
+            if candidates
+                .iter()
+                .all(|c| *c == chosen || !regex.is_match(c)) =>
+        {
+            generalized
+        }
+        _ => exact,
+    }
+}
+
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    let mut in_braces = 0usize;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                regex.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    regex.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == '\\' {
+                        regex.push_str("\\\\");
+                        continue;
+                    }
+                    regex.push(c);
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            '{' => {
+                in_braces += 1;
+                regex.push_str("(?:");
+            }
+            '}' if in_braces > 0 => {
+                in_braces -= 1;
+                regex.push(')');
+            }
+            ',' if in_braces > 0 => regex.push('|'),
+            '\\' => match chars.next() {
+                Some(escaped) => regex.push_str(&regex::escape(&escaped.to_string())),
+                None => regex.push_str("\\\\"),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{Arch, Libc, Os};
+
+    const LINUX_MUSL_X64: Platform = Platform {
+        os: Os::Linux,
+        arch: Arch::X86_64,
+        libc: Libc::Musl,
+    };
+
+    fn matcher(kind: PatternKind, pattern: &str) -> AssetMatcher {
+        AssetMatcher::new(kind, pattern).unwrap()
+    }
+
+    #[test]
+    fn test_substring_matches_anywhere() {
+        let m = matcher(PatternKind::Substring, "linux");
+        assert!(m.is_match("foo-linux-amd64.tar.gz"));
+        assert!(m.is_match("foo-linux-amd64.tar.gz.sha256"));
+        assert!(!m.is_match("foo-darwin.tar.gz"));
+    }
+
+    #[test]
+    fn test_glob_matches_whole_name() {
+        let m = matcher(PatternKind::Glob, "foo-*-linux*.tar.gz");
+        assert!(m.is_match("foo-1.2.0-linux-amd64.tar.gz"));
+        assert!(!m.is_match("foo-1.2.0-linux-amd64.tar.gz.sha256"));
+    }
+
+    #[test]
+    fn test_glob_question_mark_and_classes() {
+        let m = matcher(PatternKind::Glob, "tool-v?.[0-9]-[!d]*");
+        assert!(m.is_match("tool-v1.2-linux"));
+        assert!(!m.is_match("tool-v1.2-darwin"));
+        assert!(!m.is_match("tool-v1.x-linux"));
+    }
+
+    #[test]
+    fn test_glob_braces_are_alternatives() {
+        let m = matcher(PatternKind::Glob, "tool-*.{tar.gz,zip}");
+        assert!(m.is_match("tool-linux.tar.gz"));
+        assert!(m.is_match("tool-windows.zip"));
+        assert!(!m.is_match("tool-linux.deb"));
+    }
+
+    #[test]
+    fn test_glob_escapes_regex_metacharacters() {
+        let m = matcher(PatternKind::Glob, "tool+(x).tar.gz");
+        assert!(m.is_match("tool+(x).tar.gz"));
+        assert!(!m.is_match("toolx.tar.gz"));
+        assert!(!m.is_match("tool+(x)-tar-gz"));
+    }
+
+    #[test]
+    fn test_regex_is_anchored() {
+        let m = matcher(PatternKind::Regex, r"foo-.*-linux\.tar\.gz");
+        assert!(m.is_match("foo-1.0-linux.tar.gz"));
+        assert!(!m.is_match("foo-1.0-linux.tar.gz.sha256"));
+        assert!(!m.is_match("prefix-foo-1.0-linux.tar.gz"));
+    }
+
+    #[test]
+    fn test_regex_alternation_stays_anchored() {
+        let m = matcher(PatternKind::Regex, r"a\.zip|b\.zip");
+        assert!(m.is_match("a.zip"));
+        assert!(!m.is_match("a.zip.sig"));
+    }
+
+    #[test]
+    fn test_expand_template_substitutes_placeholders() {
+        let expanded = expand_template(
+            "tool-{version}-{target}.tar.gz",
+            PatternKind::Substring,
+            "v1.4.2",
+            &LINUX_MUSL_X64,
+        );
+        assert_eq!(expanded, "tool-1.4.2-x86_64-unknown-linux-musl.tar.gz");
+    }
+
+    #[test]
+    fn test_expand_template_os_arch_and_tag() {
+        let expanded = expand_template(
+            "{tag}/{os}/{arch}",
+            PatternKind::Substring,
+            "v2.0",
+            &LINUX_MUSL_X64,
+        );
+        assert_eq!(expanded, "v2.0/linux/x86_64");
+    }
+
+    #[test]
+    fn test_expand_template_go_names() {
+        let expanded = expand_template(
+            "tool_{version}_{goos}_{goarch}.tar.gz",
+            PatternKind::Substring,
+            "v2.0",
+            &LINUX_MUSL_X64,
+        );
+        assert_eq!(expanded, "tool_2.0_linux_amd64.tar.gz");
+    }
+
+    #[test]
+    fn test_expand_template_escapes_values_for_regex() {
+        let m = AssetMatcher::for_release(
+            PatternKind::Regex,
+            r"tool-{version}-.*\.zip",
+            "v1.4.2",
+            &LINUX_MUSL_X64,
+        )
+        .unwrap();
+        assert!(m.is_match("tool-1.4.2-linux.zip"));
+        assert!(!m.is_match("tool-1x4x2-linux.zip"));
+    }
+
+    #[test]
+    fn test_expand_template_keeps_glob_braces() {
+        let m = AssetMatcher::for_release(
+            PatternKind::Glob,
+            "tool-{version}-{arch}.{tar.gz,zip}",
+            "v1.4.2",
+            &LINUX_MUSL_X64,
+        )
+        .unwrap();
+        assert!(m.is_match("tool-1.4.2-x86_64.zip"));
+        assert!(m.is_match("tool-1.4.2-x86_64.tar.gz"));
+        assert!(!m.is_match("tool-1.4.3-x86_64.zip"));
+    }
+
+    #[test]
+    fn test_default_excludes_drop_checksums_and_signatures() {
+        let filter = ExcludeFilter::new(&default_excludes(), &[]).unwrap();
+        assert!(filter.is_excluded("tool-linux.tar.gz.sha256"));
+        assert!(filter.is_excluded("tool-linux.tar.gz.SIG"));
+        assert!(filter.is_excluded("tool_1.0_checksums.txt"));
+        assert!(filter.is_excluded("tool.sbom.json"));
+        assert!(!filter.is_excluded("tool-linux.tar.gz"));
+    }
+
+    #[test]
+    fn test_default_excludes_match_checksum_detection() {
+        let filter = ExcludeFilter::new(&default_excludes(), &[]).unwrap();
+        for name in [
+            "tool.tar.gz.sha1",
+            "SHA256SUMS",
+            "tool.intoto.jsonl",
+            "tool.pem",
+        ] {
+            assert!(crate::asset_score::is_checksum_or_signature(name));
+            assert!(filter.is_excluded(name));
+        }
+    }
+
+    #[test]
+    fn test_defaults_can_be_replaced_or_dropped() {
+        let filter = ExcludeFilter::new(&["*.txt".to_string()], &[]).unwrap();
+        assert!(filter.is_excluded("notes.txt"));
+        assert!(!filter.is_excluded("tool.tar.gz.sha256"));
+
+        let filter = ExcludeFilter::new(&[], &[]).unwrap();
+        assert!(!filter.is_excluded("tool.tar.gz.sha256"));
+    }
+
+    #[test]
+    fn test_app_excludes_are_added_to_defaults() {
+        let excludes = ["*.deb".to_string(), "*-debug*".to_string()];
+        let filter = ExcludeFilter::new(&default_excludes(), &excludes).unwrap();
+        assert!(filter.is_excluded("tool_1.0_amd64.deb"));
+        assert!(filter.is_excluded("tool-debug-linux.tar.gz"));
+        assert!(filter.is_excluded("tool.tar.gz.sha256"));
+        assert!(!filter.is_excluded("tool-linux.tar.gz"));
+    }
+
+    #[test]
+    fn test_invalid_exclude_is_rejected() {
+        let result = ExcludeFilter::new(&[], &["foo[".to_string()]);
+        assert!(matches!(result, Err(GrabError::InvalidInput(msg)) if msg.contains("exclude")));
+    }
+
+    #[test]
+    fn test_tightened_pattern_generalises_version() {
+        let candidates = [
+            "tool-1.4.2-x86_64-unknown-linux-gnu.tar.gz",
+            "tool-1.4.2-x86_64-unknown-linux-musl.tar.gz",
+        ];
+        let pattern = tightened_pattern(candidates[1], "v1.4.2", &candidates);
+        let m = matcher(PatternKind::Regex, &pattern);
+
+        assert!(m.is_match("tool-1.5.0-x86_64-unknown-linux-musl.tar.gz"));
+        assert!(!m.is_match(candidates[0]));
+    }
+
+    #[test]
+    fn test_tightened_pattern_without_version_is_exact() {
+        let pattern = tightened_pattern("tool-linux.tar.gz", "nightly", &["tool-linux.tar.gz"]);
+        let m = matcher(PatternKind::Regex, &pattern);
+
+        assert!(m.is_match("tool-linux.tar.gz"));
+        assert!(!m.is_match("tool-linux-tar-gz"));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_rejected() {
+        let result = AssetMatcher::new(PatternKind::Regex, "foo(");
+        assert!(matches!(result, Err(GrabError::InvalidInput(msg)) if msg.contains("regex")));
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        let result = AssetMatcher::new(PatternKind::Glob, "foo[");
+        assert!(matches!(result, Err(GrabError::InvalidInput(msg)) if msg.contains("glob")));
+    }
+}
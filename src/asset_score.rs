@@ -0,0 +1,281 @@
+use crate::platform::{Arch, Libc, Os, Platform, contains_token};
+
+pub const CHECKSUM_SUFFIXES: [&str; 14] = [
+    ".sha256",
+    ".sha256sum",
+    ".sha512",
+    ".sha512sum",
+    ".sha1",
+    ".md5",
+    ".b3",
+    ".sig",
+    ".asc",
+    ".minisig",
+    ".pem",
+    ".sbom",
+    ".intoto.jsonl",
+    ".json",
+];
+
+pub const CHECKSUM_MARKERS: [&str; 3] = ["checksums", "sha256sums", "sha512sums"];
+
+const PACKAGE_SUFFIXES: [&str; 8] = [
+    ".deb",
+    ".rpm",
+    ".apk",
+    ".msi",
+    ".dmg",
+    ".pkg",
+    ".appimage",
+    ".vsix",
+];
+
+pub fn detect_os(name: &str) -> Option<Os> {
+    Os::all()
+        .iter()
+        .copied()
+        .find(|os| os.aliases().iter().any(|alias| contains_token(name, alias)))
+}
+
+pub fn detect_arch(name: &str) -> Option<Arch> {
+    Arch::all().iter().copied().find(|arch| {
+        arch.aliases()
+            .iter()
+            .any(|alias| contains_token(name, alias))
+    })
+}
+
+pub fn detect_libc(name: &str) -> Libc {
+    [Libc::Musl, Libc::Gnu]
+        .into_iter()
+        .find(|libc| {
+            libc.aliases()
+                .iter()
+                .any(|alias| contains_token(name, alias))
+        })
+        .unwrap_or(Libc::None)
+}
+
+pub fn is_checksum_or_signature(name: &str) -> bool {
+    let name = name.to_lowercase();
+    CHECKSUM_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+        || CHECKSUM_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+pub fn score_asset(name: &str, platform: &Platform) -> Option<i32> {
+    let name = name.to_lowercase();
+
+    if is_checksum_or_signature(&name) {
+        return Some(-1000);
+    }
+
+    let mut score = 0;
+
+    match detect_os(&name) {
+        Some(os) if os == platform.os => score += 100,
+        Some(_) => return None,
+        None => {}
+    }
+
+    match detect_arch(&name) {
+        Some(arch) if arch == platform.arch => score += 50,
+        Some(_) => return None,
+        None if platform.os == Os::MacOs && contains_token(&name, "universal") => score += 40,
+        None => {}
+    }
+
+    score += match (platform.libc, detect_libc(&name)) {
+        (Libc::Musl, Libc::Gnu) => -50,
+        (Libc::Musl, Libc::Musl) => 10,
+        (Libc::Gnu, Libc::Gnu) => 10,
+        (Libc::Gnu, Libc::Musl) => 8,
+        _ => 0,
+    };
+
+    score += archive_score(&name, platform);
+
+    Some(score)
+}
+
+fn archive_score(name: &str, platform: &Platform) -> i32 {
+    if PACKAGE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) {
+        return -30;
+    }
+
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") || name.ends_with(".tar.xz") {
+        5
+    } else if name.ends_with(".zip") {
+        if platform.os == Os::Windows { 6 } else { 4 }
+    } else if name.ends_with(".tar.zst") || name.ends_with(".tar.bz2") {
+        4
+    } else if name.ends_with(".exe") {
+        if platform.os == Os::Windows { 5 } else { -30 }
+    } else if name.ends_with(".gz") {
+        2
+    } else if !name.contains('.') {
+        3
+    } else {
+        0
+    }
+}
+
+pub fn top_matches<'a, T>(
+    candidates: &'a [T],
+    name: impl Fn(&T) -> &str,
+    platform: &Platform,
+) -> Vec<&'a T> {
+    let scored: Vec<(i32, &T)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            score_asset(name(candidate), platform)
+                .filter(|score| *score > 0)
+                .map(|score| (score, candidate))
+        })
+        .collect();
+
+    let Some(best) = scored.iter().map(|(score, _)| *score).max() else {
+        return Vec::new();
+    };
+
+    scored
+        .into_iter()
+        .filter(|(score, _)| *score == best)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINUX_GNU_X64: Platform = Platform {
+        os: Os::Linux,
+        arch: Arch::X86_64,
+        libc: Libc::Gnu,
+    };
+
+    const LINUX_MUSL_ARM64: Platform = Platform {
+        os: Os::Linux,
+        arch: Arch::Aarch64,
+        libc: Libc::Musl,
+    };
+
+    const MACOS_ARM64: Platform = Platform {
+        os: Os::MacOs,
+        arch: Arch::Aarch64,
+        libc: Libc::None,
+    };
+
+    fn best<'a>(names: &'a [&'a str], platform: &Platform) -> Option<&'a str> {
+        match top_matches(names, |name| name, platform).as_slice() {
+            [name] => Some(**name),
+            _ => None,
+        }
+    }
+
+    const RIPGREP: [&str; 8] = [
+        "ripgrep-14.1.1-aarch64-unknown-linux-gnu.tar.gz",
+        "ripgrep-14.1.1-aarch64-unknown-linux-gnu.tar.gz.sha256",
+        "ripgrep-14.1.1-x86_64-apple-darwin.tar.gz",
+        "ripgrep-14.1.1-aarch64-apple-darwin.tar.gz",
+        "ripgrep-14.1.1-x86_64-pc-windows-msvc.zip",
+        "ripgrep-14.1.1-x86_64-unknown-linux-musl.tar.gz",
+        "ripgrep-14.1.1-x86_64-unknown-linux-musl.tar.gz.sha256",
+        "ripgrep_14.1.1-1_amd64.deb",
+    ];
+
+    #[test]
+    fn test_detect_arch_aliases() {
+        assert_eq!(detect_arch("tool_linux_amd64"), Some(Arch::X86_64));
+        assert_eq!(detect_arch("tool-x86_64-linux"), Some(Arch::X86_64));
+        assert_eq!(detect_arch("tool-arm64-darwin"), Some(Arch::Aarch64));
+        assert_eq!(detect_arch("tool-linux-386"), Some(Arch::X86));
+        assert_eq!(detect_arch("tool-linux-armv7"), Some(Arch::Arm));
+        assert_eq!(detect_arch("tool.tar.gz"), None);
+    }
+
+    #[test]
+    fn test_detect_os_aliases() {
+        assert_eq!(detect_os("tool-x86_64-apple-darwin"), Some(Os::MacOs));
+        assert_eq!(detect_os("tool-windows-amd64.zip"), Some(Os::Windows));
+        assert_eq!(detect_os("tool-linux"), Some(Os::Linux));
+        assert_eq!(detect_os("tool.tar.gz"), None);
+    }
+
+    #[test]
+    fn test_checksum_files_are_penalised() {
+        let checksum = score_asset("tool-linux-amd64.tar.gz.sha256", &LINUX_GNU_X64).unwrap();
+        let archive = score_asset("tool-linux-amd64.tar.gz", &LINUX_GNU_X64).unwrap();
+        assert!(checksum < 0);
+        assert!(archive > checksum);
+        assert!(is_checksum_or_signature("checksums.txt"));
+    }
+
+    #[test]
+    fn test_other_platforms_are_rejected() {
+        assert!(score_asset("tool-darwin-amd64.tar.gz", &LINUX_GNU_X64).is_none());
+        assert!(score_asset("tool-linux-arm64.tar.gz", &LINUX_GNU_X64).is_none());
+    }
+
+    #[test]
+    fn test_best_match_for_linux_gnu_x86_64() {
+        assert_eq!(
+            best(&RIPGREP, &LINUX_GNU_X64),
+            Some("ripgrep-14.1.1-x86_64-unknown-linux-musl.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_best_match_for_linux_musl_aarch64_rejects_gnu() {
+        let names = [
+            "tool-aarch64-unknown-linux-gnu.tar.gz",
+            "tool-aarch64-unknown-linux-musl.tar.gz",
+        ];
+        assert_eq!(
+            best(&names, &LINUX_MUSL_ARM64),
+            Some("tool-aarch64-unknown-linux-musl.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_best_match_for_macos_arm64() {
+        assert_eq!(
+            best(&RIPGREP, &MACOS_ARM64),
+            Some("ripgrep-14.1.1-aarch64-apple-darwin.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_best_match_prefers_gnu_on_gnu_host() {
+        let names = [
+            "tool-x86_64-unknown-linux-musl.tar.gz",
+            "tool-x86_64-unknown-linux-gnu.tar.gz",
+        ];
+        assert_eq!(
+            best(&names, &LINUX_GNU_X64),
+            Some("tool-x86_64-unknown-linux-gnu.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_top_matches_returns_all_ties() {
+        let names = [
+            "tool-linux-amd64.tar.gz",
+            "tool-linux-x86_64.tar.gz",
+            "tool-linux-amd64.deb",
+        ];
+        let top = top_matches(&names, |name| name, &LINUX_GNU_X64);
+        assert_eq!(
+            top,
+            vec![&"tool-linux-amd64.tar.gz", &"tool-linux-x86_64.tar.gz"]
+        );
+    }
+
+    #[test]
+    fn test_best_match_none_when_nothing_fits() {
+        let names = ["tool-windows-amd64.zip", "checksums.txt"];
+        assert_eq!(best(&names, &LINUX_GNU_X64), None);
+    }
+}
@@ -0,0 +1,501 @@
+use crate::checksum::{self, HashAlgorithm};
+use crate::config::Config;
+use crate::download::{self, Download, Verification};
+use crate::error::{GrabError, Result};
+use crate::output::expand_home;
+use crate::ui;
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const INDEX_FILE: &str = "index.json";
+const BLOBS_DIR: &str = "blobs";
+const LOCK_FILE: &str = "index.lock";
+pub const DEFAULT_MAX_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+struct IndexLock {
+    _file: File,
+    _guard: MutexGuard<'static, ()>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    pub name: String,
+    pub sha256: String,
+    pub size: u64,
+    pub last_used: u64,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf, max_size: u64) -> Self {
+        Self { dir, max_size }
+    }
+
+    pub fn open(config: &Config) -> Result<Self> {
+        let dir = match &config.cache_dir {
+            Some(dir) => expand_home(dir),
+            None => BaseDirs::new()
+                .map(|dirs| dirs.cache_dir().join("grab"))
+                .ok_or_else(|| GrabError::NotFound("Could not find cache directory".to_string()))?,
+        };
+
+        let max_size = match &config.cache_max_size {
+            Some(size) => ui::parse_size(size).ok_or_else(|| {
+                GrabError::InvalidInput(format!(
+                    "Invalid cache_max_size '{size}', expected a size such as 500M or 2G"
+                ))
+            })?,
+            None => DEFAULT_MAX_SIZE,
+        };
+
+        Ok(Self::new(dir, max_size))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join(INDEX_FILE)
+    }
+
+    fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(BLOBS_DIR).join(sha256)
+    }
+
+    pub fn entries(&self) -> Result<Vec<CacheEntry>> {
+        let path = self.index_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = File::open(path)?;
+        serde_json::from_reader(file).map_err(|e| GrabError::Parse(e.to_string()))
+    }
+
+    fn save_entries(&self, entries: &[CacheEntry]) -> Result<()> {
+        let mut file = tempfile::Builder::new()
+            .prefix(INDEX_FILE)
+            .suffix(".tmp")
+            .tempfile_in(&self.dir)?;
+        serde_json::to_writer_pretty(&mut file, entries)
+            .map_err(|e| GrabError::Parse(e.to_string()))?;
+        file.as_file().sync_all()?;
+        file.persist(self.index_path())
+            .map_err(|e| GrabError::Io(e.error))?;
+        Ok(())
+    }
+
+    fn lock_index(&self) -> Result<IndexLock> {
+        let guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        fs::create_dir_all(&self.dir)?;
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(LOCK_FILE))?;
+        file.lock()?;
+        Ok(IndexLock {
+            _file: file,
+            _guard: guard,
+        })
+    }
+
+    fn update<T>(&self, change: impl FnOnce(&mut Vec<CacheEntry>) -> T) -> Result<T> {
+        self.try_update(|entries| Ok(change(entries)))
+    }
+
+    fn try_update<T>(&self, change: impl FnOnce(&mut Vec<CacheEntry>) -> Result<T>) -> Result<T> {
+        let _lock = self.lock_index()?;
+        let mut entries = self.entries()?;
+        let result = change(&mut entries)?;
+        self.save_entries(&entries)?;
+        Ok(result)
+    }
+
+    async fn blocking<T: Send + 'static>(
+        &self,
+        task: impl FnOnce(&Cache) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || task(&cache)).await?
+    }
+
+    pub async fn fetch(
+        &self,
+        url: &str,
+        path: &Path,
+        expected_size: Option<u64>,
+        verification: &Verification,
+        force: bool,
+    ) -> Result<Option<Download>> {
+        let entry = self
+            .blocking(|cache| cache.entries())
+            .await?
+            .into_iter()
+            .find(|entry| entry.url == url && expected_size.is_none_or(|size| size == entry.size));
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+
+        let blob = self.blob_path(&entry.sha256);
+        let usable = match fs::metadata(&blob) {
+            Ok(metadata) if metadata.len() == entry.size => {
+                let sha256 = checksum::hash_file(&blob, HashAlgorithm::Sha256).await?;
+                sha256 == entry.sha256
+                    && verification
+                        .verify(&blob, &entry.name, &sha256)
+                        .await
+                        .is_ok()
+            }
+            _ => false,
+        };
+
+        let url = url.to_string();
+        if !usable {
+            self.blocking(move |cache| cache.remove_where(|e| e.url == url))
+                .await?;
+            return Ok(None);
+        }
+
+        download::copy_to_file(&blob, path, force).await?;
+        self.blocking(move |cache| {
+            cache.update(|entries| {
+                if let Some(stored) = entries.iter_mut().find(|e| e.url == url) {
+                    stored.last_used = now();
+                }
+            })
+        })
+        .await?;
+
+        Ok(Some(Download {
+            path: path.to_path_buf(),
+            size: entry.size,
+            sha256: entry.sha256,
+        }))
+    }
+
+    pub async fn insert(&self, url: &str, download: &Download) -> Result<()> {
+        let blob = self.blob_path(&download.sha256);
+        let copied = if blob.exists() {
+            None
+        } else {
+            let blobs = self.dir.join(BLOBS_DIR);
+            tokio::fs::create_dir_all(&blobs).await?;
+            let temp_path = tempfile::Builder::new()
+                .suffix(".tmp")
+                .tempfile_in(&blobs)?
+                .into_temp_path();
+            tokio::fs::copy(&download.path, &temp_path).await?;
+            Some(temp_path)
+        };
+
+        let name = url.rsplit('/').next().unwrap_or(url).to_string();
+        let entry = CacheEntry {
+            url: url.to_string(),
+            name,
+            sha256: download.sha256.clone(),
+            size: download.size,
+            last_used: now(),
+        };
+
+        self.blocking(move |cache| {
+            let over_limit = cache.try_update(|entries| {
+                if !blob.exists() {
+                    let Some(temp_path) = copied else {
+                        return Ok(false);
+                    };
+                    temp_path
+                        .persist(&blob)
+                        .map_err(|e| GrabError::Io(e.error))?;
+                }
+                entries.retain(|e| e.url != entry.url);
+                entries.push(entry);
+                Ok(entries.iter().map(|e| e.size).sum::<u64>() > cache.max_size)
+            })?;
+            if over_limit {
+                cache.prune(cache.max_size, None)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    pub fn prune(&self, max_size: u64, max_age: Option<u64>) -> Result<Vec<CacheEntry>> {
+        let cutoff = max_age.map(|age| now().saturating_sub(age));
+        let evicted = self.update(|entries| {
+            let evicted = select_evictions(entries, max_size, cutoff);
+            entries.retain(|e| !evicted.contains(e));
+            evicted
+        })?;
+        self.remove_unreferenced_blobs()?;
+        Ok(evicted)
+    }
+
+    pub fn clear(&self) -> Result<usize> {
+        let removed = self.update(std::mem::take)?.len();
+        self.remove_unreferenced_blobs()?;
+        Ok(removed)
+    }
+
+    fn remove_where(&self, predicate: impl Fn(&CacheEntry) -> bool) -> Result<()> {
+        self.update(|entries| entries.retain(|e| !predicate(e)))?;
+        self.remove_unreferenced_blobs()
+    }
+
+    fn remove_unreferenced_blobs(&self) -> Result<()> {
+        let _lock = self.lock_index()?;
+        let referenced: HashSet<String> = self.entries()?.into_iter().map(|e| e.sha256).collect();
+
+        let Ok(blobs) = fs::read_dir(self.dir.join(BLOBS_DIR)) else {
+            return Ok(());
+        };
+        for blob in blobs.flatten() {
+            let name = blob.file_name().to_string_lossy().to_string();
+            if !referenced.contains(&name) && !name.ends_with(".tmp") {
+                fs::remove_file(blob.path())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn select_evictions(
+    entries: &[CacheEntry],
+    max_size: u64,
+    cutoff: Option<u64>,
+) -> Vec<CacheEntry> {
+    let mut by_age: Vec<&CacheEntry> = entries.iter().collect();
+    by_age.sort_by_key(|e| e.last_used);
+
+    let mut total: u64 = entries.iter().map(|e| e.size).sum();
+    let mut evicted = Vec::new();
+
+    for entry in by_age {
+        let expired = cutoff.is_some_and(|cutoff| entry.last_used < cutoff);
+        if expired || total > max_size {
+            total -= entry.size;
+            evicted.push(entry.clone());
+        }
+    }
+
+    evicted
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn entry(url: &str, size: u64, last_used: u64) -> CacheEntry {
+        CacheEntry {
+            url: url.to_string(),
+            name: url.to_string(),
+            sha256: format!("{url}-sha"),
+            size,
+            last_used,
+        }
+    }
+
+    fn urls(entries: &[CacheEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_select_evictions_least_recently_used_first() {
+        let entries = [
+            entry("a", 10, 300),
+            entry("b", 10, 100),
+            entry("c", 10, 200),
+        ];
+        let evicted = select_evictions(&entries, 15, None);
+        assert_eq!(urls(&evicted), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_select_evictions_under_limit_keeps_everything() {
+        let entries = [entry("a", 10, 300), entry("b", 10, 100)];
+        assert!(select_evictions(&entries, 20, None).is_empty());
+    }
+
+    #[test]
+    fn test_select_evictions_by_age() {
+        let entries = [entry("a", 10, 300), entry("b", 10, 100)];
+        let evicted = select_evictions(&entries, u64::MAX, Some(200));
+        assert_eq!(urls(&evicted), vec!["b"]);
+    }
+
+    async fn downloaded(dir: &Path, name: &str, content: &[u8]) -> Download {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        Download {
+            sha256: checksum::hash_file(&path, HashAlgorithm::Sha256)
+                .await
+                .unwrap(),
+            size: content.len() as u64,
+            path,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_then_fetch_hit() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("cache"), DEFAULT_MAX_SIZE);
+        let download = downloaded(dir.path(), "tool.tar.gz", b"tool").await;
+        cache
+            .insert("https://x/tool.tar.gz", &download)
+            .await
+            .unwrap();
+
+        let target = dir.path().join("copy.tar.gz");
+        let hit = cache
+            .fetch(
+                "https://x/tool.tar.gz",
+                &target,
+                Some(4),
+                &Verification::default(),
+                false,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(hit.sha256, download.sha256);
+        assert_eq!(std::fs::read(&target).unwrap(), b"tool");
+        assert_eq!(cache.entries().unwrap()[0].name, "tool.tar.gz");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_misses_on_size_or_checksum_mismatch() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("cache"), DEFAULT_MAX_SIZE);
+        let download = downloaded(dir.path(), "tool", b"tool").await;
+        cache.insert("https://x/tool", &download).await.unwrap();
+        let target = dir.path().join("copy");
+
+        let miss = cache
+            .fetch(
+                "https://x/tool",
+                &target,
+                Some(5),
+                &Verification::default(),
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(miss.is_none());
+
+        let verification = Verification {
+            checksum: Some(checksum::Checksum {
+                algorithm: Some(HashAlgorithm::Sha256),
+                digest: "0".repeat(64),
+            }),
+            signatures: Vec::new(),
+        };
+        let miss = cache
+            .fetch("https://x/tool", &target, None, &verification, false)
+            .await
+            .unwrap();
+        assert!(miss.is_none());
+        assert!(cache.entries().unwrap().is_empty());
+        assert!(!target.exists());
+    }
+
+    #[tokio::test]
+    async fn test_insert_enforces_size_limit_and_clear_removes_blobs() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("cache"), 6);
+        let first = downloaded(dir.path(), "a", b"aaaa").await;
+        let second = downloaded(dir.path(), "b", b"bbbb").await;
+
+        cache.insert("https://x/a", &first).await.unwrap();
+        cache.insert("https://x/b", &second).await.unwrap();
+        assert_eq!(urls(&cache.entries().unwrap()), vec!["https://x/b"]);
+        assert!(!cache.blob_path(&first.sha256).exists());
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(!cache.blob_path(&second.sha256).exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_inserts_keep_every_indexed_blob() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("cache"), DEFAULT_MAX_SIZE);
+
+        let mut tasks = Vec::new();
+        for i in 0..16 {
+            let download =
+                downloaded(dir.path(), &format!("tool-{i}"), format!("{i}").as_bytes()).await;
+            let cache = cache.clone();
+            tasks.push(tokio::spawn(async move {
+                cache
+                    .insert(&format!("https://x/tool-{i}"), &download)
+                    .await
+                    .unwrap();
+                cache.prune(DEFAULT_MAX_SIZE, None).unwrap();
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let entries = cache.entries().unwrap();
+        assert_eq!(entries.len(), 16);
+        assert!(entries.iter().all(|e| cache.blob_path(&e.sha256).exists()));
+        let leftovers = std::fs::read_dir(cache.dir())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_misses_on_corrupted_blob() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("cache"), DEFAULT_MAX_SIZE);
+        let download = downloaded(dir.path(), "tool", b"tool").await;
+        cache.insert("https://x/tool", &download).await.unwrap();
+        std::fs::write(cache.blob_path(&download.sha256), b"evil").unwrap();
+
+        let target = dir.path().join("copy");
+        let miss = cache
+            .fetch(
+                "https://x/tool",
+                &target,
+                Some(4),
+                &Verification::default(),
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(miss.is_none());
+        assert!(cache.entries().unwrap().is_empty());
+        assert!(!target.exists());
+    }
+}
@@ -0,0 +1,348 @@
+use crate::error::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt;
+use std::path::Path;
+use tokio::fs::File as TokioFile;
+use tokio::io::AsyncReadExt;
+
+static BSD_LINE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(SHA256|SHA512|SHA2-256|SHA2-512|BLAKE3)\s*\((.+)\)\s*=\s*([0-9a-f]+)$")
+        .expect("Invalid regex check the BSD checksum pattern syntax.")
+});
+
+static GNU_LINE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([0-9a-fA-F]{64}|[0-9a-fA-F]{128})\s+\*?(.+)$")
+        .expect("Invalid regex check the GNU checksum pattern syntax.")
+});
+
+static BARE_HASH_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([0-9a-fA-F]{64}|[0-9a-fA-F]{128})$")
+        .expect("Invalid regex check the bare checksum pattern syntax.")
+});
+
+const PER_ASSET_SUFFIXES: [&str; 6] = [
+    ".sha512",
+    ".sha512sum",
+    ".sha256",
+    ".sha256sum",
+    ".b3",
+    ".blake3",
+];
+
+const CHECKSUM_LIST_MARKERS: [&str; 5] = [
+    "sha512sums",
+    "sha256sums",
+    "b3sums",
+    "checksums",
+    "sums.txt",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+const AMBIGUOUS_DIGEST_LEN: usize = 64;
+const AMBIGUOUS_ALGORITHMS: [HashAlgorithm; 2] = [HashAlgorithm::Sha256, HashAlgorithm::Blake3];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: Option<HashAlgorithm>,
+    pub digest: String,
+}
+
+pub enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl HashAlgorithm {
+    fn from_file_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.contains("sha512") {
+            Some(HashAlgorithm::Sha512)
+        } else if name.contains("sha256") {
+            Some(HashAlgorithm::Sha256)
+        } else if name.contains("blake3") || name.contains("b3sum") || name.ends_with(".b3") {
+            Some(HashAlgorithm::Blake3)
+        } else {
+            None
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label.to_uppercase().as_str() {
+            "SHA256" | "SHA2-256" => Some(HashAlgorithm::Sha256),
+            "SHA512" | "SHA2-512" => Some(HashAlgorithm::Sha512),
+            "BLAKE3" => Some(HashAlgorithm::Blake3),
+            _ => None,
+        }
+    }
+
+    fn from_digest_len(len: usize) -> Option<Self> {
+        (len == HashAlgorithm::Sha512.digest_len()).then_some(HashAlgorithm::Sha512)
+    }
+
+    fn digest_len(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 | HashAlgorithm::Blake3 => 64,
+            HashAlgorithm::Sha512 => 128,
+        }
+    }
+
+    pub fn hasher(&self) -> Hasher {
+        match self {
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Sha512 => Hasher::Sha512(Sha512::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+}
+
+impl Hasher {
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha512(hasher) => hasher.update(data),
+            Hasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    pub fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha512(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+impl Checksum {
+    fn new(algorithm: Option<HashAlgorithm>, digest: &str) -> Option<Self> {
+        let expected_len =
+            algorithm.map_or(AMBIGUOUS_DIGEST_LEN, |algorithm| algorithm.digest_len());
+        (digest.len() == expected_len).then(|| Self {
+            algorithm,
+            digest: digest.to_lowercase(),
+        })
+    }
+
+    pub fn algorithms(&self) -> Vec<HashAlgorithm> {
+        match self.algorithm {
+            Some(algorithm) => vec![algorithm],
+            None => AMBIGUOUS_ALGORITHMS.to_vec(),
+        }
+    }
+
+    pub fn algorithm_label(&self) -> String {
+        self.algorithms()
+            .iter()
+            .map(HashAlgorithm::to_string)
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+
+    pub fn matches(&self, digest: &str) -> bool {
+        self.digest.eq_ignore_ascii_case(digest)
+    }
+}
+
+pub fn is_per_asset_file(file_name: &str, asset_name: &str) -> bool {
+    file_name
+        .strip_prefix(asset_name)
+        .is_some_and(|suffix| PER_ASSET_SUFFIXES.contains(&suffix.to_lowercase().as_str()))
+}
+
+pub fn is_checksum_list(file_name: &str) -> bool {
+    let name = file_name.to_lowercase();
+    CHECKSUM_LIST_MARKERS
+        .iter()
+        .any(|marker| name.contains(marker))
+        && !name.ends_with(".sig")
+        && !name.ends_with(".asc")
+        && !name.ends_with(".pem")
+        && !name.ends_with(".minisig")
+}
+
+pub fn parse_checksum(content: &str, file_name: &str, asset_name: &str) -> Option<Checksum> {
+    let file_algorithm = HashAlgorithm::from_file_name(file_name);
+    let per_asset = is_per_asset_file(file_name, asset_name);
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(caps) = BSD_LINE_RE.captures(line) {
+            if same_file(&caps[2], asset_name) {
+                return HashAlgorithm::from_label(&caps[1])
+                    .and_then(|algorithm| Checksum::new(Some(algorithm), &caps[3]));
+            }
+            continue;
+        }
+
+        let (digest, name) = match GNU_LINE_RE.captures(line) {
+            Some(caps) => (caps.get(1)?.as_str(), Some(caps.get(2)?.as_str())),
+            None if per_asset && BARE_HASH_RE.is_match(line) => (line, None),
+            None => continue,
+        };
+
+        if name.is_none_or(|name| same_file(name, asset_name)) {
+            let algorithm = file_algorithm.or_else(|| HashAlgorithm::from_digest_len(digest.len()));
+            return Checksum::new(algorithm, digest);
+        }
+    }
+
+    None
+}
+
+fn same_file(listed: &str, asset_name: &str) -> bool {
+    let listed = listed.trim();
+    let base = listed.rsplit(['/', '\\']).next().unwrap_or(listed);
+    base == asset_name
+}
+
+pub async fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    let mut file = TokioFile::open(path).await?;
+    let mut hasher = algorithm.hasher();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(hasher.finalize_hex());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgorithm::Sha256 => write!(f, "sha256"),
+            HashAlgorithm::Sha512 => write!(f, "sha512"),
+            HashAlgorithm::Blake3 => write!(f, "blake3"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSET: &str = "tool-1.0.0-x86_64-unknown-linux-musl.tar.gz";
+    const SHA256: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn sha512() -> String {
+        "ab".repeat(64)
+    }
+
+    #[test]
+    fn test_parse_gnu_format() {
+        let content = format!("{}  other.zip\n{SHA256}  {ASSET}\n", "0".repeat(64));
+        let checksum = parse_checksum(&content, "SHA256SUMS", ASSET).unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Sha256));
+        assert_eq!(checksum.digest, SHA256);
+    }
+
+    #[test]
+    fn test_parse_gnu_binary_marker_and_path() {
+        let content = format!("{SHA256} *./dist/{ASSET}\n");
+        let checksum = parse_checksum(&content, "checksums.txt", ASSET).unwrap();
+        assert_eq!(checksum.digest, SHA256);
+    }
+
+    #[test]
+    fn test_parse_bsd_format() {
+        let content = format!("SHA512 ({ASSET}) = {}\n", sha512());
+        let checksum = parse_checksum(&content, "CHECKSUMS", ASSET).unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Sha512));
+        assert_eq!(checksum.digest, sha512());
+    }
+
+    #[test]
+    fn test_parse_bare_hash_only_for_per_asset_files() {
+        let content = format!("{}\n", SHA256.to_uppercase());
+        let per_asset = format!("{ASSET}.sha256");
+
+        let checksum = parse_checksum(&content, &per_asset, ASSET).unwrap();
+        assert_eq!(checksum.digest, SHA256);
+        assert!(parse_checksum(&content, "checksums.txt", ASSET).is_none());
+    }
+
+    #[test]
+    fn test_algorithm_from_file_name_and_length() {
+        let content = format!("{SHA256}  {ASSET}\n");
+        let blake = parse_checksum(&content, "b3sums.txt", ASSET).unwrap();
+        assert_eq!(blake.algorithm, Some(HashAlgorithm::Blake3));
+
+        let content = format!("{}  {ASSET}\n", sha512());
+        let long = parse_checksum(&content, "checksums.txt", ASSET).unwrap();
+        assert_eq!(long.algorithm, Some(HashAlgorithm::Sha512));
+
+        let content = format!("{SHA256}  {ASSET}\n");
+        let ambiguous = parse_checksum(&content, "checksums.txt", ASSET).unwrap();
+        assert_eq!(ambiguous.algorithm, None);
+        assert_eq!(
+            ambiguous.algorithms(),
+            vec![HashAlgorithm::Sha256, HashAlgorithm::Blake3]
+        );
+        assert_eq!(ambiguous.algorithm_label(), "sha256 or blake3");
+    }
+
+    #[test]
+    fn test_parse_ignores_other_assets() {
+        let content = format!("{SHA256}  {ASSET}.sig\n{SHA256}  other-{ASSET}\n");
+        assert!(parse_checksum(&content, "SHA256SUMS", ASSET).is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_wrong_digest_length() {
+        let content = format!("{}  {ASSET}\n", sha512());
+        assert!(parse_checksum(&content, "SHA256SUMS", ASSET).is_none());
+    }
+
+    #[test]
+    fn test_checksum_file_detection() {
+        assert!(is_per_asset_file(&format!("{ASSET}.sha256"), ASSET));
+        assert!(is_per_asset_file(&format!("{ASSET}.B3"), ASSET));
+        assert!(!is_per_asset_file(&format!("{ASSET}.sig"), ASSET));
+        assert!(is_checksum_list("tool_1.0.0_checksums.txt"));
+        assert!(is_checksum_list("SHA256SUMS"));
+        assert!(!is_checksum_list("SHA256SUMS.asc"));
+        assert!(!is_checksum_list(ASSET));
+    }
+
+    #[test]
+    fn test_hashers_produce_known_digests() {
+        let mut sha = HashAlgorithm::Sha256.hasher();
+        sha.update(b"test");
+        assert_eq!(sha.finalize_hex(), SHA256);
+
+        let mut blake = HashAlgorithm::Blake3.hasher();
+        blake.update(b"");
+        assert_eq!(
+            blake.finalize_hex(),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hash_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        std::fs::write(&path, b"test").unwrap();
+
+        assert_eq!(
+            hash_file(&path, HashAlgorithm::Sha256).await.unwrap(),
+            SHA256
+        );
+    }
+}
@@ -1,8 +1,19 @@
 use std::fmt;
 
+use crate::asset_pattern::ExcludeFilter;
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::download::DownloadOptions;
 use crate::error::{GrabError, Result};
-use crate::models::App;
+use crate::github_version::Discovery;
+use crate::models::{App, InstallRule, PatternKind, SourceArchive};
+use crate::output::{OutputOptions, safe_file_name};
+use crate::ratelimit::{RateLimiter, parse_rate};
+use crate::ui;
 use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 #[derive(Parser)]
 #[command(
@@ -20,12 +31,16 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     Add(AddArgs),
+    Cache(CacheArgs),
     Check(CheckArgs),
-    Download,
+    Download(DownloadArgs),
+    Install(InstallArgs),
     List,
     Remote(RemoteArgs),
     Remove(RemoveArgs),
+    Rollback(RollbackArgs),
     Search(SearchArgs),
+    Uninstall(UninstallArgs),
 }
 
 #[derive(Args, Debug)]
@@ -39,12 +54,89 @@ pub struct AddArgs {
     #[arg(short, long)]
     pub repo: Option<String>,
 
-    #[arg(short, long, help = "Pattern to match release assets")]
+    #[arg(
+        short,
+        long,
+        help = "Pattern to match release assets, may use {version}, {tag}, {os} (linux, darwin, windows), {arch} (x86_64, aarch64, armv7), {goos} and {goarch} (Go names such as amd64 and arm64) and {target} (Rust target triple); auto-detected if omitted"
+    )]
     pub asset_pattern: Option<String>,
 
+    #[arg(
+        short = 'k',
+        long,
+        value_enum,
+        default_value_t = PatternKind::Substring,
+        help = "How the asset pattern is matched against asset names"
+    )]
+    pub pattern_kind: PatternKind,
+
     #[arg(short, long, help = "Version flag")]
     pub version_flag: Option<String>,
 
+    #[arg(
+        short = 'x',
+        long,
+        help = "Glob of release assets to ignore, can be repeated (e.g. '*.deb')"
+    )]
+    pub exclude: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Do not apply the default excludes (checksums, signatures, SBOMs) to this app"
+    )]
+    pub no_default_excludes: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Download the source archive of the latest tag when there are no release assets"
+    )]
+    pub source: Option<SourceArchive>,
+
+    #[arg(long, help = "Directory this app's downloads are written to")]
+    pub dest: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Fail the download when the release publishes no checksum for the asset"
+    )]
+    pub require_checksum: bool,
+
+    #[arg(
+        long,
+        help = "Minisign public key that must have signed the asset (<asset>.minisig)"
+    )]
+    pub minisign_key: Option<String>,
+
+    #[arg(
+        long,
+        help = "SSH public key that must have signed the asset with ssh-keygen -Y sign (<asset>.sig)"
+    )]
+    pub ssh_key: Option<String>,
+
+    #[arg(
+        long,
+        requires = "ssh_key",
+        help = "Namespace the SSH signature was made with [default: file]"
+    )]
+    pub ssh_namespace: Option<String>,
+
+    #[arg(long, help = "Name of the installed binary [default: app name]")]
+    pub bin: Option<String>,
+
+    #[arg(
+        long,
+        help = "Glob of the binary inside the release archive, matched against the path when it contains '/' (e.g. '*/bin/rg')"
+    )]
+    pub bin_path: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = InstallRule::parse,
+        help = "Install files matching GLOB into TARGET (bin, man1, bash, zsh, fish or share), can be repeated (e.g. 'doc/rg.1=man1')"
+    )]
+    pub install_file: Vec<InstallRule>,
+
     #[arg(short, long, help = "Enable interactive mode")]
     pub interactive: bool,
 }
@@ -55,19 +147,118 @@ impl AddArgs {
     }
 
     pub fn into_app(self) -> Result<App> {
-        App::new(
+        let mut app = App::new(
             self.name
                 .ok_or_else(|| GrabError::InvalidInput("Name of the app is required".into()))?,
             self.owner
                 .ok_or_else(|| GrabError::InvalidInput("Owner of the repo is required".into()))?,
             self.repo
                 .ok_or_else(|| GrabError::InvalidInput("Name of the repo is required".into()))?,
-            self.asset_pattern.ok_or_else(|| {
-                GrabError::InvalidInput("Asset pattern of the repo is required".into())
-            })?,
+            self.asset_pattern.unwrap_or_default(),
             self.version_flag
                 .ok_or_else(|| GrabError::InvalidInput("Version flag is required".into()))?,
-        )
+        )?;
+
+        app.pattern_kind = self.pattern_kind;
+        app.exclude = self.exclude;
+        app.no_default_excludes = self.no_default_excludes;
+        app.source = self.source;
+        app.dest = self.dest;
+        app.require_checksum = self.require_checksum;
+        app.minisign_key = self.minisign_key;
+        app.ssh_key = self.ssh_key;
+        app.ssh_namespace = self.ssh_namespace;
+        app.bin = self.bin;
+        app.bin_path = self.bin_path;
+        app.install = self.install_file;
+
+        safe_file_name(&app.name)?;
+        safe_file_name(app.bin_name())?;
+        Ok(app)
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct TransferArgs {
+    #[arg(
+        short,
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u8).range(1..=16),
+        help = "Parallel connections per download for servers that support ranges"
+    )]
+    pub connections: u8,
+
+    #[arg(
+        short,
+        long,
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "Maximum number of apps checked or downloaded at the same time"
+    )]
+    pub jobs: Option<u16>,
+
+    #[arg(
+        long,
+        value_parser = parse_rate,
+        help = "Limit total download bandwidth across all downloads, e.g. 500K or 5M"
+    )]
+    pub limit_rate: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Directory downloads are written to, overrides per-app and config destinations"
+    )]
+    pub dest: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Downloaded file name, may use {name}, {version}, {tag}, {asset}, {stem} and {ext}"
+    )]
+    pub output_template: Option<String>,
+
+    #[arg(long, help = "Overwrite files that already exist at the destination")]
+    pub force: bool,
+
+    #[arg(
+        long,
+        help = "Always download from the network and do not store the result"
+    )]
+    pub no_cache: bool,
+
+    #[arg(
+        long,
+        help = "Unpack downloaded tar.gz, tar.xz, tar.zst, zip and gz archives next to the download"
+    )]
+    pub extract: bool,
+}
+
+impl TransferArgs {
+    pub fn download_options(&self, config: &Config) -> Result<DownloadOptions> {
+        let jobs = self.jobs.map_or_else(|| config.jobs(), usize::from);
+
+        let output = OutputOptions {
+            dir: self.dest.clone(),
+            default_dir: config.download_dir.clone(),
+            template: self
+                .output_template
+                .clone()
+                .or_else(|| config.output_template.clone()),
+        };
+        output.validate()?;
+        let default_excludes = config.default_excludes();
+        ExcludeFilter::new(&default_excludes, &[])?;
+
+        Ok(DownloadOptions {
+            connections: self.connections as usize,
+            jobs: Arc::new(Semaphore::new(jobs)),
+            rate_limit: RateLimiter::new(self.limit_rate),
+            output,
+            force: self.force,
+            extract: self.extract,
+            cache: (!self.no_cache).then(|| Cache::open(config)).transpose()?,
+            default_excludes,
+            ..DownloadOptions::default()
+        })
     }
 }
 
@@ -75,12 +266,93 @@ impl AddArgs {
 pub struct CheckArgs {
     #[arg(short, long)]
     pub download: bool,
+
+    #[arg(
+        long,
+        help = "Install updates into the bin directory instead of downloading them"
+    )]
+    pub install: bool,
+
+    #[arg(
+        long,
+        help = "Directory binaries are installed into [default: bin_dir or ~/.local/bin]"
+    )]
+    pub bin_dir: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub transfer: TransferArgs,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Discovery::Api,
+        help = "How to discover the latest release (atom avoids the REST API rate limit)"
+    )]
+    pub discovery: Discovery,
+}
+
+#[derive(Args, Debug)]
+pub struct DownloadArgs {
+    #[command(flatten)]
+    pub transfer: TransferArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct InstallArgs {
+    #[arg(help = "Names of the apps to install [default: all apps]")]
+    pub names: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Directory binaries are installed into [default: bin_dir or ~/.local/bin]"
+    )]
+    pub bin_dir: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub transfer: TransferArgs,
 }
 
 #[derive(Args, Debug)]
 pub struct RemoteArgs {
     #[arg(short, long)]
     pub file: Option<String>,
+
+    #[command(flatten)]
+    pub transfer: TransferArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    List,
+    Prune(PruneArgs),
+    Clear,
+}
+
+#[derive(Args, Debug)]
+pub struct PruneArgs {
+    #[arg(
+        long,
+        value_parser = parse_cache_size,
+        help = "Evict least recently used entries until the cache fits, e.g. 500M [default: cache_max_size]"
+    )]
+    pub max_size: Option<u64>,
+
+    #[arg(long, help = "Evict entries not used for this many days")]
+    pub older_than: Option<u64>,
+}
+
+fn parse_cache_size(value: &str) -> Result<u64> {
+    ui::parse_size(value).ok_or_else(|| {
+        GrabError::InvalidInput(format!(
+            "Invalid size '{value}', expected a number with an optional K, M or G suffix"
+        ))
+    })
 }
 
 #[derive(Args, Debug)]
@@ -89,6 +361,30 @@ pub struct RemoveArgs {
     pub index: usize,
 }
 
+#[derive(Args, Debug)]
+pub struct RollbackArgs {
+    #[arg(help = "Name of the app to roll back")]
+    pub name: String,
+
+    #[arg(
+        long,
+        help = "Installed version to switch to [default: the previous install]"
+    )]
+    pub to: Option<String>,
+
+    #[arg(long, help = "Replace files not managed by grab at the link locations")]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct UninstallArgs {
+    #[arg(help = "Name of the app to uninstall")]
+    pub name: String,
+
+    #[arg(long, help = "Also remove files that were modified since installation")]
+    pub force: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct SearchArgs {
     #[arg(short, long)]
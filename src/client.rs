@@ -1,8 +1,14 @@
 use crate::error::Result;
-use reqwest::Client;
+use reqwest::header::{HeaderMap, LINK};
+use reqwest::{Client, Response, StatusCode};
 use serde::de::DeserializeOwned;
+use std::io;
 use std::time::Duration;
 
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
 const APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 pub fn github_latest_release_url(owner: &str, repo: &str) -> String {
@@ -12,19 +18,54 @@ pub fn github_latest_release_url(owner: &str, repo: &str) -> String {
     )
 }
 
+pub fn github_tags_url(owner: &str, repo: &str) -> String {
+    format!(
+        "https://api.github.com/repos/{}/{}/tags?per_page=100",
+        owner, repo
+    )
+}
+
+pub fn github_releases_feed_url(owner: &str, repo: &str) -> String {
+    format!("https://github.com/{}/{}/releases.atom", owner, repo)
+}
+
+pub fn github_release_html_url(owner: &str, repo: &str, tag: &str) -> String {
+    format!("https://github.com/{}/{}/releases/tag/{}", owner, repo, tag)
+}
+
+pub fn github_tag_html_url(owner: &str, repo: &str, tag: &str) -> String {
+    format!("https://github.com/{}/{}/tree/{}", owner, repo, tag)
+}
+
+pub fn github_source_archive_url(owner: &str, repo: &str, tag: &str, extension: &str) -> String {
+    format!(
+        "https://github.com/{}/{}/archive/refs/tags/{}.{}",
+        owner, repo, tag, extension
+    )
+}
+
 #[derive(Debug, Clone)]
 pub struct RequestClient {
     pub client: Client,
+    stream_client: Client,
 }
 
 impl RequestClient {
     pub fn new() -> Result<Self> {
         let client = Client::builder()
-            .timeout(Duration::from_secs(30))
+            .timeout(API_TIMEOUT)
+            .read_timeout(READ_TIMEOUT)
+            .user_agent(APP_USER_AGENT)
+            .build()?;
+        let stream_client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
             .user_agent(APP_USER_AGENT)
             .build()?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            stream_client,
+        })
     }
 
     pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
@@ -34,9 +75,101 @@ impl RequestClient {
         Ok(json)
     }
 
-    pub async fn download_bytes(&self, url: &str) -> Result<Vec<u8>> {
+    pub async fn get_json_pages<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        max_pages: usize,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut next = Some(url.to_string());
+
+        for _ in 0..max_pages {
+            let Some(url) = next.take() else {
+                break;
+            };
+            let response = self.client.get(&url).send().await?;
+            let response = response.error_for_status()?;
+            next = response
+                .headers()
+                .get(LINK)
+                .and_then(|value| value.to_str().ok())
+                .and_then(next_page_url);
+            items.extend(response.json::<Vec<T>>().await?);
+        }
+
+        Ok(items)
+    }
+
+    pub async fn get_json_optional<T: DeserializeOwned>(&self, url: &str) -> Result<Option<T>> {
         let response = self.client.get(url).send().await?;
-        let bytes = response.bytes().await?;
-        Ok(bytes.to_vec())
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let response = response.error_for_status()?;
+        let json = response.json::<T>().await?;
+        Ok(Some(json))
+    }
+
+    pub async fn get_text(&self, url: &str) -> Result<String> {
+        let response = self.client.get(url).send().await?;
+        let response = response.error_for_status()?;
+        let text = response.text().await?;
+        Ok(text)
+    }
+
+    pub async fn get_stream(&self, url: &str, headers: HeaderMap) -> Result<Response> {
+        let request = self.stream_client.get(url).headers(headers).send();
+        with_stall_timeout(request).await
+    }
+}
+
+pub async fn with_stall_timeout<T>(read: impl Future<Output = reqwest::Result<T>>) -> Result<T> {
+    match tokio::time::timeout(READ_TIMEOUT, read).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!(
+                "download stalled, no data received for {} seconds",
+                READ_TIMEOUT.as_secs()
+            ),
+        )
+        .into()),
+    }
+}
+
+pub fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_page_url_from_link_header() {
+        let link = r#"<https://api.github.com/repositories/1/tags?per_page=100&page=2>; rel="next", <https://api.github.com/repositories/1/tags?per_page=100&page=5>; rel="last""#;
+        assert_eq!(
+            next_page_url(link).as_deref(),
+            Some("https://api.github.com/repositories/1/tags?per_page=100&page=2")
+        );
+    }
+
+    #[test]
+    fn test_next_page_url_on_last_page() {
+        let link = r#"<https://api.github.com/repositories/1/tags?per_page=100&page=1>; rel="prev", <https://api.github.com/repositories/1/tags?per_page=100&page=1>; rel="first""#;
+        assert!(next_page_url(link).is_none());
+        assert!(next_page_url("").is_none());
     }
 }
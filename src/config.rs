@@ -0,0 +1,149 @@
+use crate::asset_pattern;
+use crate::error::{GrabError, Result};
+use directories::UserDirs;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = ".grab.json";
+pub const DEFAULT_JOBS: usize = 4;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_max_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bin_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_excludes: Option<Vec<String>>,
+}
+
+impl Config {
+    pub fn default_excludes(&self) -> Vec<String> {
+        self.default_excludes
+            .clone()
+            .unwrap_or_else(asset_pattern::default_excludes)
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs.filter(|jobs| *jobs > 0).unwrap_or(DEFAULT_JOBS)
+    }
+}
+
+fn get_config_path() -> Result<PathBuf> {
+    if let Some(user_dirs) = UserDirs::new() {
+        Ok(user_dirs.home_dir().join(CONFIG_FILE))
+    } else {
+        Err(GrabError::NotFound(
+            "Could not find home directory".to_string(),
+        ))
+    }
+}
+
+pub fn load_config() -> Result<Config> {
+    let path = get_config_path()?;
+    load_config_from(&path)
+}
+
+pub fn load_config_from(path: &Path) -> Result<Config> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let file = File::open(path)?;
+    serde_json::from_reader(file).map_err(|e| GrabError::Parse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_missing_config_uses_defaults() {
+        let dir = tempdir().unwrap();
+        let config = load_config_from(&dir.path().join("missing.json")).unwrap();
+
+        assert!(config.jobs.is_none());
+        assert_eq!(config.jobs(), DEFAULT_JOBS);
+    }
+
+    #[test]
+    fn test_config_jobs_is_loaded() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{ "jobs": 2 }"#).unwrap();
+
+        assert_eq!(load_config_from(&path).unwrap().jobs(), 2);
+    }
+
+    #[test]
+    fn test_config_output_defaults_are_loaded() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(
+            &path,
+            r#"{ "download_dir": "~/Downloads", "output_template": "{name}-{version}{ext}" }"#,
+        )
+        .unwrap();
+
+        let config = load_config_from(&path).unwrap();
+        assert_eq!(config.download_dir, Some(PathBuf::from("~/Downloads")));
+        assert_eq!(
+            config.output_template.as_deref(),
+            Some("{name}-{version}{ext}")
+        );
+    }
+
+    #[test]
+    fn test_config_default_excludes_override_builtin_list() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{ "default_excludes": ["*.txt"] }"#).unwrap();
+
+        let config = load_config_from(&path).unwrap();
+        assert_eq!(config.default_excludes(), vec!["*.txt"]);
+        assert_eq!(
+            Config::default().default_excludes(),
+            asset_pattern::default_excludes()
+        );
+    }
+
+    #[test]
+    fn test_zero_jobs_falls_back_to_default() {
+        let config = Config {
+            jobs: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(config.jobs(), DEFAULT_JOBS);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_ignored() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{ "colour": "blue" }"#).unwrap();
+
+        assert!(load_config_from(&path).unwrap().jobs.is_none());
+    }
+
+    #[test]
+    fn test_invalid_config_returns_parse_error() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, b"{{ nope").unwrap();
+
+        assert!(matches!(load_config_from(&path), Err(GrabError::Parse(_))));
+    }
+}
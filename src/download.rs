@@ -0,0 +1,706 @@
+use crate::asset_pattern;
+use crate::cache::Cache;
+use crate::checksum::{self, Checksum, HashAlgorithm};
+use crate::client::{RequestClient, with_stall_timeout};
+use crate::config::DEFAULT_JOBS;
+use crate::error::{GrabError, Result};
+use crate::output::OutputOptions;
+use crate::progress::{Progress, Transfer};
+use crate::ratelimit::RateLimiter;
+use crate::signature::Signature;
+use reqwest::header::{
+    CONTENT_RANGE, ETAG, HeaderMap, HeaderValue, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs::{self, File as TokioFile, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+const PART_SUFFIX: &str = ".part";
+const META_SUFFIX: &str = ".part.json";
+const MIN_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    pub connections: usize,
+    pub jobs: Arc<Semaphore>,
+    pub progress: Progress,
+    pub rate_limit: RateLimiter,
+    pub output: OutputOptions,
+    pub force: bool,
+    pub cache: Option<Cache>,
+    pub extract: bool,
+    pub default_excludes: Vec<String>,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            connections: 1,
+            jobs: Arc::new(Semaphore::new(DEFAULT_JOBS)),
+            progress: Progress::new(),
+            rate_limit: RateLimiter::default(),
+            output: OutputOptions::default(),
+            force: false,
+            cache: None,
+            extract: false,
+            default_excludes: asset_pattern::default_excludes(),
+        }
+    }
+}
+
+impl DownloadOptions {
+    pub async fn acquire_job(&self) -> Result<OwnedSemaphorePermit> {
+        Ok(self.jobs.clone().acquire_owned().await?)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Verification {
+    pub checksum: Option<Checksum>,
+    pub signatures: Vec<Signature>,
+}
+
+impl Verification {
+    pub async fn verify(&self, path: &Path, name: &str, sha256: &str) -> Result<()> {
+        if let Some(checksum) = &self.checksum {
+            let mut mismatch = None;
+            for algorithm in checksum.algorithms() {
+                let actual = match algorithm {
+                    HashAlgorithm::Sha256 => sha256.to_string(),
+                    algorithm => checksum::hash_file(path, algorithm).await?,
+                };
+                if checksum.matches(&actual) {
+                    mismatch = None;
+                    break;
+                }
+                mismatch.get_or_insert(actual);
+            }
+
+            if let Some(actual) = mismatch {
+                return Err(GrabError::ChecksumMismatch {
+                    name: name.to_string(),
+                    algorithm: checksum.algorithm_label(),
+                    expected: checksum.digest.clone(),
+                    actual,
+                });
+            }
+        }
+
+        for signature in &self.signatures {
+            signature.verify(path, name).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Download {
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartMeta {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+impl PartMeta {
+    pub fn validator(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+pub async fn download_to_file(
+    client: &RequestClient,
+    url: &str,
+    path: &Path,
+    expected_size: Option<u64>,
+    verification: &Verification,
+    options: &DownloadOptions,
+) -> Result<Download> {
+    let name = path.display().to_string();
+    let (part_path, meta_path) = part_paths(path);
+    let resume = resume_point(&part_path, &meta_path, url).await;
+
+    if resume.is_none()
+        && let Some(size) = expected_size
+    {
+        let ranges = split_ranges(size, options.connections);
+        if ranges.len() > 1
+            && let Some(download) =
+                download_chunked(client, url, path, size, &ranges, verification, options).await?
+        {
+            return Ok(download);
+        }
+    }
+
+    let (mut response, offset) = request(client, url, resume.as_ref()).await?;
+    let total = response.content_length().map(|len| len + offset);
+
+    if let (Some(expected), Some(actual)) = (expected_size, total)
+        && expected != actual
+    {
+        discard_part(&part_path, &meta_path).await;
+        return Err(GrabError::SizeMismatch {
+            name,
+            expected,
+            actual,
+        });
+    }
+
+    let meta = PartMeta {
+        url: url.to_string(),
+        etag: header_string(&response, ETAG),
+        last_modified: header_string(&response, LAST_MODIFIED),
+        size: expected_size.or(total),
+    };
+    save_part_meta(&meta_path, &meta)?;
+
+    let mut hasher = Sha256::new();
+    let transfer = options.progress.start(&name, meta.size);
+    if offset > 0 {
+        options
+            .progress
+            .println(format!("Resuming {} at {} bytes", name, offset));
+        hash_file(&part_path, &mut hasher).await?;
+        transfer.resume_at(offset);
+    }
+
+    let streamed = async {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(offset > 0)
+            .truncate(offset == 0)
+            .open(&part_path)
+            .await?;
+        let mut size = offset;
+
+        while let Some(chunk) = with_stall_timeout(response.chunk()).await? {
+            options.rate_limit.consume(chunk.len()).await;
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+            size += chunk.len() as u64;
+            transfer.inc(chunk.len() as u64);
+        }
+
+        file.flush().await?;
+        file.sync_all().await?;
+        Ok::<u64, GrabError>(size)
+    }
+    .await;
+
+    if streamed.is_err() {
+        transfer.abandon();
+    }
+    let size = streamed?;
+    transfer.finish();
+
+    if let Some(expected) = expected_size
+        && expected != size
+    {
+        discard_part(&part_path, &meta_path).await;
+        return Err(GrabError::SizeMismatch {
+            name,
+            expected,
+            actual: size,
+        });
+    }
+
+    let sha256 = format!("{:x}", hasher.finalize());
+    if let Err(e) = verification.verify(&part_path, &name, &sha256).await {
+        discard_part(&part_path, &meta_path).await;
+        return Err(e);
+    }
+
+    let finalized = finalize(&part_path, path, options.force).await;
+    let _ = fs::remove_file(&meta_path).await;
+    finalized?;
+
+    Ok(Download {
+        path: path.to_path_buf(),
+        size,
+        sha256,
+    })
+}
+
+async fn download_chunked(
+    client: &RequestClient,
+    url: &str,
+    path: &Path,
+    size: u64,
+    ranges: &[(u64, u64)],
+    verification: &Verification,
+    options: &DownloadOptions,
+) -> Result<Option<Download>> {
+    let (first_start, first_end) = ranges[0];
+    let probe = client
+        .get_stream(url, range_headers(first_start, first_end)?)
+        .await?;
+
+    let supports_ranges = probe.status() == StatusCode::PARTIAL_CONTENT
+        && content_range(&probe) == Some((first_start, Some(size)));
+    if !supports_ranges {
+        return Ok(None);
+    }
+
+    options.progress.println(format!(
+        "Downloading {} in {} parallel chunks",
+        path.display(),
+        ranges.len()
+    ));
+    let transfer = options
+        .progress
+        .start(&path.display().to_string(), Some(size));
+
+    let (part_path, _) = part_paths(path);
+    let file = TokioFile::create(&part_path).await?;
+    file.set_len(size).await?;
+    drop(file);
+
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut probe = Some(probe);
+
+    for &(start, end) in ranges {
+        let client = client.clone();
+        let url = url.to_string();
+        let part_path = part_path.clone();
+        let response = probe.take();
+        let transfer = transfer.clone();
+        let rate_limit = options.rate_limit.clone();
+
+        tasks.spawn(async move {
+            let response = match response {
+                Some(response) => response,
+                None => get_range(&client, &url, start, end).await?,
+            };
+            write_range(response, &part_path, start, end, &transfer, &rate_limit).await
+        });
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        if let Err(e) = result.map_err(GrabError::TaskJoin).and_then(|r| r) {
+            tasks.abort_all();
+            transfer.abandon();
+            let _ = fs::remove_file(&part_path).await;
+            return Err(e);
+        }
+    }
+
+    transfer.finish();
+
+    let mut hasher = Sha256::new();
+    hash_file(&part_path, &mut hasher).await?;
+    TokioFile::open(&part_path).await?.sync_all().await?;
+
+    let sha256 = format!("{:x}", hasher.finalize());
+    let name = path.display().to_string();
+    if let Err(e) = verification.verify(&part_path, &name, &sha256).await {
+        let _ = fs::remove_file(&part_path).await;
+        return Err(e);
+    }
+
+    finalize(&part_path, path, options.force).await?;
+
+    Ok(Some(Download {
+        path: path.to_path_buf(),
+        size,
+        sha256,
+    }))
+}
+
+async fn get_range(client: &RequestClient, url: &str, start: u64, end: u64) -> Result<Response> {
+    let response = client.get_stream(url, range_headers(start, end)?).await?;
+    Ok(response.error_for_status()?)
+}
+
+fn range_headers(start: u64, end: u64) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    headers.insert(RANGE, header_value(&format!("bytes={start}-{end}"))?);
+    Ok(headers)
+}
+
+async fn write_range(
+    mut response: Response,
+    path: &Path,
+    start: u64,
+    end: u64,
+    transfer: &Transfer,
+    rate_limit: &RateLimiter,
+) -> Result<()> {
+    let name = path.display().to_string();
+    if response.status() != StatusCode::PARTIAL_CONTENT
+        || content_range(&response).map(|(s, _)| s) != Some(start)
+    {
+        return Err(GrabError::InvalidInput(format!(
+            "Server ignored range {start}-{end} for {name}"
+        )));
+    }
+
+    let mut file = OpenOptions::new().write(true).open(path).await?;
+    file.seek(SeekFrom::Start(start)).await?;
+
+    let expected = end - start + 1;
+    let mut written = 0u64;
+    while let Some(chunk) = with_stall_timeout(response.chunk()).await? {
+        if written + chunk.len() as u64 > expected {
+            break;
+        }
+        rate_limit.consume(chunk.len()).await;
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+        transfer.inc(chunk.len() as u64);
+    }
+    file.flush().await?;
+
+    if written != expected {
+        return Err(GrabError::SizeMismatch {
+            name,
+            expected,
+            actual: written,
+        });
+    }
+
+    Ok(())
+}
+
+pub async fn copy_to_file(source: &Path, path: &Path, force: bool) -> Result<()> {
+    let (part_path, _) = part_paths(path);
+    let copied = async {
+        fs::copy(source, &part_path).await?;
+        TokioFile::open(&part_path).await?.sync_all().await?;
+        finalize(&part_path, path, force).await
+    }
+    .await;
+
+    if copied.is_err() {
+        let _ = fs::remove_file(&part_path).await;
+    }
+    copied
+}
+
+async fn finalize(part_path: &Path, path: &Path, force: bool) -> Result<()> {
+    if force {
+        fs::rename(part_path, path).await?;
+    } else {
+        let part = tempfile::TempPath::from_path(part_path);
+        if let Err(e) = part.persist_noclobber(path) {
+            if e.error.kind() == std::io::ErrorKind::AlreadyExists {
+                return Err(GrabError::FileExists(path.display().to_string()));
+            }
+            return Err(e.error.into());
+        }
+    }
+
+    #[cfg(unix)]
+    std::fs::File::open(destination_dir(path))?.sync_all()?;
+
+    Ok(())
+}
+
+fn content_range(response: &Response) -> Option<(u64, Option<u64>)> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_content_range)
+}
+
+pub fn split_ranges(size: u64, connections: usize) -> Vec<(u64, u64)> {
+    if size == 0 {
+        return Vec::new();
+    }
+
+    let max_chunks = size.div_ceil(MIN_CHUNK_SIZE).max(1);
+    let chunks = (connections.max(1) as u64).min(max_chunks);
+    let chunk_size = size.div_ceil(chunks);
+
+    (0..chunks)
+        .map(|i| i * chunk_size)
+        .take_while(|start| *start < size)
+        .map(|start| (start, (start + chunk_size).min(size) - 1))
+        .collect()
+}
+
+async fn request(
+    client: &RequestClient,
+    url: &str,
+    resume: Option<&(u64, PartMeta)>,
+) -> Result<(Response, u64)> {
+    if let Some((offset, meta)) = resume
+        && let Some(validator) = meta.validator()
+    {
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, header_value(&format!("bytes={offset}-"))?);
+        headers.insert(IF_RANGE, header_value(validator)?);
+
+        let response = client.get_stream(url, headers).await?;
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let start = content_range(&response).map(|(start, _)| start);
+                if start == Some(*offset) {
+                    return Ok((response, *offset));
+                }
+            }
+            StatusCode::OK => return Ok((response, 0)),
+            _ => {}
+        }
+    }
+
+    let response = client
+        .get_stream(url, HeaderMap::new())
+        .await?
+        .error_for_status()?;
+    Ok((response, 0))
+}
+
+async fn resume_point(part_path: &Path, meta_path: &Path, url: &str) -> Option<(u64, PartMeta)> {
+    let meta = load_part_meta(meta_path)?;
+    if meta.url != url || meta.validator().is_none() {
+        return None;
+    }
+
+    let len = fs::metadata(part_path).await.ok()?.len();
+    (len > 0).then_some((len, meta))
+}
+
+async fn hash_file(path: &Path, hasher: &mut Sha256) -> Result<()> {
+    let mut file = TokioFile::open(path).await?;
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+async fn discard_part(part_path: &Path, meta_path: &Path) {
+    let _ = fs::remove_file(part_path).await;
+    let _ = fs::remove_file(meta_path).await;
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value)
+        .map_err(|e| GrabError::InvalidInput(format!("Invalid header value '{value}': {e}")))
+}
+
+fn header_string(response: &Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn destination_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+pub fn part_paths(path: &Path) -> (PathBuf, PathBuf) {
+    let mut part = path.as_os_str().to_owned();
+    part.push(PART_SUFFIX);
+    let mut meta = path.as_os_str().to_owned();
+    meta.push(META_SUFFIX);
+    (part.into(), meta.into())
+}
+
+pub fn load_part_meta(path: &Path) -> Option<PartMeta> {
+    let file = std::fs::File::open(path).ok()?;
+    serde_json::from_reader(file).ok()
+}
+
+pub fn save_part_meta(path: &Path, meta: &PartMeta) -> Result<()> {
+    let file = std::fs::File::create(path)?;
+    serde_json::to_writer_pretty(file, meta).map_err(|e| GrabError::Parse(e.to_string()))
+}
+
+pub fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (span, total) = range.split_once('/')?;
+    let (start, _end) = span.split_once('-')?;
+
+    let start = start.trim().parse().ok()?;
+    let total = match total.trim() {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+
+    Some((start, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn meta(etag: Option<&str>, last_modified: Option<&str>) -> PartMeta {
+        PartMeta {
+            url: "https://example.com/tool.tar.gz".to_string(),
+            etag: etag.map(str::to_string),
+            last_modified: last_modified.map(str::to_string),
+            size: Some(42),
+        }
+    }
+
+    #[test]
+    fn test_part_paths_append_suffixes() {
+        let (part, meta) = part_paths(Path::new("dir/tool.tar.gz"));
+        assert_eq!(part, PathBuf::from("dir/tool.tar.gz.part"));
+        assert_eq!(meta, PathBuf::from("dir/tool.tar.gz.part.json"));
+    }
+
+    #[test]
+    fn test_parse_content_range_with_total() {
+        assert_eq!(
+            parse_content_range("bytes 100-199/200"),
+            Some((100, Some(200)))
+        );
+    }
+
+    #[test]
+    fn test_parse_content_range_unknown_total() {
+        assert_eq!(parse_content_range("bytes 0-9/*"), Some((0, None)));
+    }
+
+    #[test]
+    fn test_parse_content_range_rejects_garbage() {
+        assert_eq!(parse_content_range("items 0-9/10"), None);
+        assert_eq!(parse_content_range("bytes */200"), None);
+    }
+
+    #[test]
+    fn test_split_ranges_covers_whole_file() {
+        let size = 20 * 1024 * 1024 + 3;
+        let ranges = split_ranges(size, 4);
+
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[3].1, size - 1);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].1 + 1, pair[1].0);
+        }
+    }
+
+    #[test]
+    fn test_split_ranges_single_connection() {
+        assert_eq!(split_ranges(100, 1), vec![(0, 99)]);
+    }
+
+    #[test]
+    fn test_split_ranges_small_files_are_not_split() {
+        assert_eq!(split_ranges(MIN_CHUNK_SIZE, 8).len(), 1);
+        assert_eq!(split_ranges(MIN_CHUNK_SIZE * 2, 8).len(), 2);
+        assert!(split_ranges(0, 8).is_empty());
+    }
+
+    #[test]
+    fn test_validator_prefers_strong_etag() {
+        assert_eq!(
+            meta(Some("\"abc\""), Some("Mon")).validator(),
+            Some("\"abc\"")
+        );
+    }
+
+    #[test]
+    fn test_validator_skips_weak_etag() {
+        assert_eq!(
+            meta(Some("W/\"abc\""), Some("Mon")).validator(),
+            Some("Mon")
+        );
+        assert_eq!(meta(Some("W/\"abc\""), None).validator(), None);
+    }
+
+    #[test]
+    fn test_part_meta_roundtrip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tool.tar.gz.part.json");
+        let original = meta(Some("\"abc\""), None);
+
+        save_part_meta(&path, &original).unwrap();
+        assert_eq!(load_part_meta(&path), Some(original));
+    }
+
+    #[test]
+    fn test_load_part_meta_missing_or_invalid() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("missing.part.json");
+        assert_eq!(load_part_meta(&path), None);
+
+        std::fs::write(&path, b"{ not json").unwrap();
+        assert_eq!(load_part_meta(&path), None);
+    }
+
+    #[tokio::test]
+    async fn test_copy_to_file_does_not_clobber_without_force() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("source");
+        let target = dir.path().join("target");
+        std::fs::write(&source, b"new").unwrap();
+        std::fs::write(&target, b"old").unwrap();
+
+        let result = copy_to_file(&source, &target, false).await;
+        assert!(matches!(result, Err(GrabError::FileExists(_))));
+        assert_eq!(std::fs::read(&target).unwrap(), b"old");
+        assert!(!part_paths(&target).0.exists());
+
+        copy_to_file(&source, &target, true).await.unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"new");
+    }
+
+    #[tokio::test]
+    async fn test_verify_tries_blake3_for_unlabeled_64_hex_digest() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tool");
+        std::fs::write(&path, b"").unwrap();
+        let sha256 = checksum::hash_file(&path, HashAlgorithm::Sha256)
+            .await
+            .unwrap();
+        let blake3 = checksum::hash_file(&path, HashAlgorithm::Blake3)
+            .await
+            .unwrap();
+
+        let verification = |digest: String| Verification {
+            checksum: Some(Checksum {
+                algorithm: None,
+                digest,
+            }),
+            signatures: Vec::new(),
+        };
+
+        verification(blake3)
+            .verify(&path, "tool", &sha256)
+            .await
+            .unwrap();
+        verification(sha256.clone())
+            .verify(&path, "tool", &sha256)
+            .await
+            .unwrap();
+        let result = verification("0".repeat(64))
+            .verify(&path, "tool", &sha256)
+            .await;
+        assert!(matches!(result, Err(GrabError::ChecksumMismatch { .. })));
+    }
+}
@@ -33,8 +33,74 @@ pub enum GrabError {
     #[error("Task join error: {0}")]
     TaskJoin(#[from] tokio::task::JoinError),
 
+    #[error("Job queue closed: {0}")]
+    JobQueue(#[from] tokio::sync::AcquireError),
+
     #[error("Asset not found: {0}")]
     AssetNotFound(String),
+
+    #[error(
+        "Asset pattern '{pattern}' matches {} assets, use a more specific pattern:\n{}",
+        .candidates.len(),
+        .candidates.join("\n")
+    )]
+    AmbiguousAsset {
+        pattern: String,
+        candidates: Vec<String>,
+    },
+
+    #[error("Size mismatch for {name}: expected {expected} bytes, got {actual}")]
+    SizeMismatch {
+        name: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("Release not found: {0}")]
+    ReleaseNotFound(String),
+
+    #[error("Refusing to use unsafe file name \"{name}\": {reason}")]
+    UnsafeFileName { name: String, reason: String },
+
+    #[error("{0} already exists, use --force to overwrite it")]
+    FileExists(String),
+
+    #[error("{algorithm} checksum mismatch for {name}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        name: String,
+        algorithm: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Checksum missing: {0}")]
+    ChecksumMissing(String),
+
+    #[error("Bad {kind} signature for {name}: {reason}")]
+    SignatureInvalid {
+        name: String,
+        kind: String,
+        reason: String,
+    },
+
+    #[error("Signature missing: {0}")]
+    SignatureMissing(String),
+
+    #[error("Refusing to extract {entry} from {archive}: {reason}")]
+    UnsafeArchive {
+        archive: String,
+        entry: String,
+        reason: String,
+    },
+
+    #[error("Unsupported archive format: {0}")]
+    UnsupportedArchive(String),
+
+    #[error("Binary not found: {0}")]
+    BinaryNotFound(String),
+
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
 }
 
 pub type Result<T> = color_eyre::Result<T, GrabError>;
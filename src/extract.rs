@@ -0,0 +1,712 @@
+use crate::error::{GrabError, Result};
+use crate::output::safe_file_name;
+use flate2::read::GzDecoder;
+use liblzma::read::XzDecoder;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const ZIP_MAGIC: &[u8] = &[b'P', b'K', 0x03, 0x04];
+const ZIP_EMPTY_MAGIC: &[u8] = &[b'P', b'K', 0x05, 0x06];
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_MAGIC: &[u8] = b"ustar";
+const MAX_LINK_HOPS: usize = 40;
+
+const TAR_EXTENSIONS: [&str; 7] = [
+    ".tar", ".tar.gz", ".tgz", ".tar.xz", ".txz", ".tar.zst", ".tzst",
+];
+const ARCHIVE_EXTENSIONS: [&str; 11] = [
+    ".tar.gz", ".tar.xz", ".tar.zst", ".tgz", ".txz", ".tzst", ".tar", ".zip", ".gz", ".xz", ".zst",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Xz,
+    Zstd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar(Compression),
+    Zip,
+    Single(Compression),
+}
+
+#[derive(Debug, Clone)]
+pub struct Extracted {
+    pub dir: PathBuf,
+    pub files: usize,
+}
+
+pub fn detect_format(name: &str, header: &[u8]) -> Option<ArchiveFormat> {
+    let lower = name.to_lowercase();
+    let tar_name = TAR_EXTENSIONS.iter().any(|ext| lower.ends_with(ext));
+
+    let compression = if header.starts_with(GZIP_MAGIC) {
+        Compression::Gzip
+    } else if header.starts_with(XZ_MAGIC) {
+        Compression::Xz
+    } else if header.starts_with(ZSTD_MAGIC) {
+        Compression::Zstd
+    } else if header.starts_with(ZIP_MAGIC) || header.starts_with(ZIP_EMPTY_MAGIC) {
+        return Some(ArchiveFormat::Zip);
+    } else if header.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()) == Some(TAR_MAGIC)
+        || (tar_name && lower.ends_with(".tar"))
+    {
+        return Some(ArchiveFormat::Tar(Compression::None));
+    } else {
+        return None;
+    };
+
+    if tar_name {
+        Some(ArchiveFormat::Tar(compression))
+    } else {
+        Some(ArchiveFormat::Single(compression))
+    }
+}
+
+pub fn archive_stem(name: &str) -> &str {
+    let lower = name.to_lowercase();
+    ARCHIVE_EXTENSIONS
+        .iter()
+        .find(|ext| lower.ends_with(*ext) && lower.len() > ext.len())
+        .map_or(name, |ext| &name[..name.len() - ext.len()])
+}
+
+pub fn extract(archive: &Path, dest: &Path) -> Result<Extracted> {
+    let name = archive
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut header = Vec::with_capacity(TAR_MAGIC_OFFSET + TAR_MAGIC.len());
+    File::open(archive)?
+        .take((TAR_MAGIC_OFFSET + TAR_MAGIC.len()) as u64)
+        .read_to_end(&mut header)?;
+    let format =
+        detect_format(&name, &header).ok_or_else(|| GrabError::UnsupportedArchive(name.clone()))?;
+
+    let parent = dest
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    let staging = tempfile::Builder::new()
+        .prefix(".grab-extract-")
+        .tempdir_in(parent)?;
+
+    let (files, links) = match format {
+        ArchiveFormat::Tar(compression) => {
+            let reader = decoder(File::open(archive)?, compression)?;
+            unpack_tar(reader, staging.path(), &name)?
+        }
+        ArchiveFormat::Zip => unpack_zip(File::open(archive)?, staging.path(), &name)?,
+        ArchiveFormat::Single(compression) => {
+            let output = safe_file_name(single_file_name(&name))?;
+            let mut reader = decoder(File::open(archive)?, compression)?;
+            let mut file = File::create(staging.path().join(output))?;
+            io::copy(&mut reader, &mut file)?;
+            (1, Vec::new())
+        }
+    };
+
+    let root = single_top_level_dir(staging.path())?.unwrap_or_else(|| staging.path().into());
+    let stripped = root.strip_prefix(staging.path()).unwrap_or(Path::new(""));
+    let links: Vec<PathBuf> = links
+        .iter()
+        .filter_map(|link| link.strip_prefix(stripped).ok().map(Path::to_path_buf))
+        .collect();
+    check_links(&root, &links, &name)?;
+    fs::rename(&root, dest)?;
+
+    Ok(Extracted {
+        dir: dest.to_path_buf(),
+        files,
+    })
+}
+
+fn single_file_name(name: &str) -> &str {
+    let lower = name.to_lowercase();
+    [".gz", ".xz", ".zst"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext) && lower.len() > ext.len())
+        .map_or(name, |ext| &name[..name.len() - ext.len()])
+}
+
+fn decoder(file: File, compression: Compression) -> Result<Box<dyn Read>> {
+    let reader = BufReader::new(file);
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(GzDecoder::new(reader)),
+        Compression::Xz => Box::new(XzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::Decoder::with_buffer(reader)?),
+    })
+}
+
+fn unpack_tar(reader: impl Read, root: &Path, archive: &str) -> Result<(usize, Vec<PathBuf>)> {
+    let mut tar = tar::Archive::new(reader);
+    let mut files = 0;
+    let mut links = Vec::new();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let raw_path = entry.path()?.to_path_buf();
+        let Some(path) = entry_path(&raw_path, root, archive)? else {
+            continue;
+        };
+        let target = root.join(&path);
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry_type.is_symlink() {
+            let link = entry
+                .link_name()?
+                .ok_or_else(|| unsafe_entry(archive, &raw_path, "symlink has no target"))?
+                .to_path_buf();
+            create_symlink(&path, &link, root, archive)?;
+            links.push(path);
+        } else if entry_type.is_hard_link() {
+            let link = entry
+                .link_name()?
+                .ok_or_else(|| unsafe_entry(archive, &raw_path, "hard link has no target"))?
+                .to_path_buf();
+            let source = entry_path(&link, root, archive)?
+                .ok_or_else(|| unsafe_entry(archive, &raw_path, "hard link to the archive root"))?;
+            if resolve_in_root(root, &source).is_none() {
+                return Err(unsafe_entry(
+                    archive,
+                    &raw_path,
+                    "hard link points outside the archive",
+                ));
+            }
+            create_parent(&target)?;
+            fs::copy(root.join(source), &target)?;
+            files += 1;
+        } else if entry_type.is_file() || entry_type == tar::EntryType::Continuous {
+            create_parent(&target)?;
+            let mut file = File::create(&target)?;
+            io::copy(&mut entry, &mut file)?;
+            set_mode(&target, entry.header().mode().ok())?;
+            files += 1;
+        }
+    }
+
+    Ok((files, links))
+}
+
+fn unpack_zip(file: File, root: &Path, archive: &str) -> Result<(usize, Vec<PathBuf>)> {
+    let mut zip = zip::ZipArchive::new(BufReader::new(file))?;
+    let mut files = 0;
+    let mut links = Vec::new();
+
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index)?;
+        let raw_path = PathBuf::from(entry.name()?.as_ref());
+        let Some(path) = entry_path(&raw_path, root, archive)? else {
+            continue;
+        };
+        let target = root.join(&path);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.is_symlink() {
+            let mut link = String::new();
+            entry.read_to_string(&mut link)?;
+            create_symlink(&path, Path::new(&link), root, archive)?;
+            links.push(path);
+        } else {
+            create_parent(&target)?;
+            let mut file = File::create(&target)?;
+            io::copy(&mut entry, &mut file)?;
+            set_mode(&target, entry.unix_mode())?;
+            files += 1;
+        }
+    }
+
+    Ok((files, links))
+}
+
+fn entry_path(raw: &Path, root: &Path, archive: &str) -> Result<Option<PathBuf>> {
+    let path = normalize_entry(raw).map_err(|reason| unsafe_entry(archive, raw, reason))?;
+    if path.as_os_str().is_empty() {
+        return Ok(None);
+    }
+
+    let mut current = root.to_path_buf();
+    for component in path.iter() {
+        current.push(component);
+        if fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Err(unsafe_entry(
+                archive,
+                raw,
+                "it is written through a symlink",
+            ));
+        }
+    }
+
+    Ok(Some(path))
+}
+
+pub fn normalize_entry(path: &Path) -> std::result::Result<PathBuf, &'static str> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => return Err("it contains '..'"),
+            Component::RootDir | Component::Prefix(_) => return Err("it is an absolute path"),
+        }
+    }
+    Ok(normalized)
+}
+
+pub fn link_stays_inside(entry: &Path, link: &Path) -> bool {
+    if link.is_absolute() {
+        return false;
+    }
+
+    let mut depth: usize = entry.parent().map_or(0, |parent| parent.iter().count());
+    for component in link.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(parent) => depth = parent,
+                None => return false,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+fn resolve_in_root(root: &Path, path: &Path) -> Option<PathBuf> {
+    let mut resolved = PathBuf::new();
+    let mut pending: Vec<OsString> = Vec::new();
+    push_components(&mut pending, path)?;
+    let mut hops = 0;
+
+    while let Some(part) = pending.pop() {
+        if part == ".." {
+            if !resolved.pop() {
+                return None;
+            }
+            continue;
+        }
+
+        resolved.push(&part);
+        if let Ok(link) = fs::read_link(root.join(&resolved)) {
+            hops += 1;
+            if hops > MAX_LINK_HOPS {
+                return None;
+            }
+            resolved.pop();
+            push_components(&mut pending, &link)?;
+        }
+    }
+
+    Some(resolved)
+}
+
+fn push_components(pending: &mut Vec<OsString>, path: &Path) -> Option<()> {
+    for component in path.components().rev() {
+        match component {
+            Component::Normal(part) => pending.push(part.to_os_string()),
+            Component::ParentDir => pending.push("..".into()),
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(())
+}
+
+fn check_links(root: &Path, links: &[PathBuf], archive: &str) -> Result<()> {
+    for link in links {
+        if resolve_in_root(root, link).is_none() {
+            return Err(unsafe_entry(
+                archive,
+                link,
+                "symlink points outside the archive",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn create_symlink(path: &Path, link: &Path, root: &Path, archive: &str) -> Result<()> {
+    let resolved = path
+        .parent()
+        .map_or_else(|| link.to_path_buf(), |parent| parent.join(link));
+    if !link_stays_inside(path, link) || resolve_in_root(root, &resolved).is_none() {
+        return Err(unsafe_entry(
+            archive,
+            path,
+            "symlink points outside the archive",
+        ));
+    }
+
+    let target = root.join(path);
+    create_parent(&target)?;
+    let _ = fs::remove_file(&target);
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(link, &target)?;
+
+    #[cfg(not(unix))]
+    {
+        let source = target.parent().unwrap_or(root).join(link);
+        fs::copy(source, &target)?;
+    }
+
+    Ok(())
+}
+
+fn create_parent(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: Option<u32>) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let executable = mode.is_some_and(|mode| mode & 0o111 != 0);
+    let mode = if executable { 0o755 } else { 0o644 };
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: Option<u32>) -> Result<()> {
+    Ok(())
+}
+
+fn single_top_level_dir(dir: &Path) -> Result<Option<PathBuf>> {
+    let mut entries = fs::read_dir(dir)?;
+    let (Some(first), None) = (entries.next().transpose()?, entries.next()) else {
+        return Ok(None);
+    };
+
+    Ok(first.file_type()?.is_dir().then(|| first.path()))
+}
+
+fn unsafe_entry(archive: &str, entry: &Path, reason: &str) -> GrabError {
+    GrabError::UnsafeArchive {
+        archive: archive.to_string(),
+        entry: entry.display().to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn tar_bytes(build: impl FnOnce(&mut tar::Builder<Vec<u8>>)) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        build(&mut builder);
+        builder.into_inner().unwrap()
+    }
+
+    fn add_file(builder: &mut tar::Builder<Vec<u8>>, path: &str, mode: u32, content: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content).unwrap();
+    }
+
+    fn add_symlink(builder: &mut tar::Builder<Vec<u8>>, path: &str, target: &str) {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_cksum();
+        builder.append_link(&mut header, path, target).unwrap();
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn write_archive(dir: &Path, name: &str, data: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_detect_format_by_magic_and_extension() {
+        assert_eq!(
+            detect_format("tool.tar.gz", GZIP_MAGIC),
+            Some(ArchiveFormat::Tar(Compression::Gzip))
+        );
+        assert_eq!(
+            detect_format("tool.gz", GZIP_MAGIC),
+            Some(ArchiveFormat::Single(Compression::Gzip))
+        );
+        assert_eq!(
+            detect_format("tool.tar.xz", XZ_MAGIC),
+            Some(ArchiveFormat::Tar(Compression::Xz))
+        );
+        assert_eq!(
+            detect_format("tool.tzst", ZSTD_MAGIC),
+            Some(ArchiveFormat::Tar(Compression::Zstd))
+        );
+        assert_eq!(
+            detect_format("tool.bin", ZIP_MAGIC),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(detect_format("tool", b"\x7fELF"), None);
+    }
+
+    #[test]
+    fn test_detect_format_trusts_magic_over_extension() {
+        assert_eq!(
+            detect_format("tool.tar.gz", XZ_MAGIC),
+            Some(ArchiveFormat::Tar(Compression::Xz))
+        );
+        assert_eq!(
+            detect_format("tool.tar.gz", ZIP_MAGIC),
+            Some(ArchiveFormat::Zip)
+        );
+    }
+
+    #[test]
+    fn test_archive_stem() {
+        assert_eq!(archive_stem("tool-1.0-linux.tar.gz"), "tool-1.0-linux");
+        assert_eq!(archive_stem("tool-1.0.tgz"), "tool-1.0");
+        assert_eq!(archive_stem("tool.tar.xz"), "tool");
+        assert_eq!(archive_stem("tool.zip"), "tool");
+        assert_eq!(archive_stem("tool-linux-amd64.gz"), "tool-linux-amd64");
+    }
+
+    #[test]
+    fn test_normalize_entry() {
+        assert_eq!(
+            normalize_entry(Path::new("./tool/bin/rg")).unwrap(),
+            PathBuf::from("tool/bin/rg")
+        );
+        assert!(normalize_entry(Path::new("../evil")).is_err());
+        assert!(normalize_entry(Path::new("tool/../../evil")).is_err());
+        assert!(normalize_entry(Path::new("/etc/passwd")).is_err());
+    }
+
+    #[test]
+    fn test_link_stays_inside() {
+        assert!(link_stays_inside(
+            Path::new("tool/bin/rg"),
+            Path::new("../lib/rg")
+        ));
+        assert!(link_stays_inside(Path::new("rg"), Path::new("tool/rg")));
+        assert!(!link_stays_inside(
+            Path::new("tool/rg"),
+            Path::new("../../etc")
+        ));
+        assert!(!link_stays_inside(
+            Path::new("rg"),
+            Path::new("/usr/bin/rg")
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_tar_gz_strips_top_level_dir_and_keeps_exec_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let data = tar_bytes(|b| {
+            add_file(b, "tool-1.0/tool", 0o755, b"binary");
+            add_file(b, "tool-1.0/README.md", 0o644, b"readme");
+            add_symlink(b, "tool-1.0/bin/tool", "../tool");
+        });
+        let archive = write_archive(dir.path(), "tool-1.0.tar.gz", &gzip(&data));
+
+        let dest = dir.path().join("out");
+        let extracted = extract(&archive, &dest).unwrap();
+
+        assert_eq!(extracted.files, 2);
+        assert_eq!(fs::read(dest.join("tool")).unwrap(), b"binary");
+        assert_eq!(fs::read(dest.join("bin/tool")).unwrap(), b"binary");
+        let mode = fs::metadata(dest.join("tool"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+        let mode = fs::metadata(dest.join("README.md"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o644);
+    }
+
+    #[test]
+    fn test_extract_keeps_multiple_top_level_entries() {
+        let dir = tempdir().unwrap();
+        let data = tar_bytes(|b| {
+            add_file(b, "tool", 0o755, b"binary");
+            add_file(b, "LICENSE", 0o644, b"license");
+        });
+        let archive = write_archive(dir.path(), "tool.tar", &data);
+
+        let dest = dir.path().join("out");
+        extract(&archive, &dest).unwrap();
+        assert!(dest.join("tool").is_file());
+        assert!(dest.join("LICENSE").is_file());
+    }
+
+    #[test]
+    fn test_extract_rejects_symlink_escape() {
+        let dir = tempdir().unwrap();
+        let data = tar_bytes(|b| add_symlink(b, "evil", "../../etc/passwd"));
+        let archive = write_archive(dir.path(), "evil.tar", &data);
+
+        let result = extract(&archive, &dir.path().join("out"));
+        assert!(matches!(result, Err(GrabError::UnsafeArchive { .. })));
+        assert!(!dir.path().join("out").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_rejects_symlink_escape_through_other_symlinks() {
+        let dir = tempdir().unwrap();
+        let data = tar_bytes(|b| {
+            add_symlink(b, "pkg/a", ".");
+            add_symlink(b, "pkg/l", "a/a/a/../../../..");
+        });
+        let archive = write_archive(dir.path(), "evil.tar", &data);
+
+        let result = extract(&archive, &dir.path().join("out"));
+        assert!(matches!(result, Err(GrabError::UnsafeArchive { .. })));
+        assert!(!dir.path().join("out").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_rejects_symlink_escaping_stripped_top_level_dir() {
+        let dir = tempdir().unwrap();
+        let data = tar_bytes(|b| {
+            add_file(b, "pkg/file", 0o644, b"file");
+            add_symlink(b, "pkg/up", "..");
+        });
+        let archive = write_archive(dir.path(), "evil.tar", &data);
+
+        let result = extract(&archive, &dir.path().join("out"));
+        assert!(matches!(result, Err(GrabError::UnsafeArchive { .. })));
+        assert!(!dir.path().join("out").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_rejects_symlink_escape_through_later_symlinks() {
+        let dir = tempdir().unwrap();
+        let data = tar_bytes(|b| {
+            add_symlink(b, "pkg/l", "a/b/../../..");
+            add_symlink(b, "pkg/a", ".");
+            add_file(b, "pkg/b/file", 0o644, b"file");
+        });
+        let archive = write_archive(dir.path(), "evil.tar", &data);
+
+        let result = extract(&archive, &dir.path().join("out"));
+        assert!(matches!(result, Err(GrabError::UnsafeArchive { .. })));
+        assert!(!dir.path().join("out").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_in_root_follows_extracted_symlinks() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("pkg/lib")).unwrap();
+        std::os::unix::fs::symlink("lib", dir.path().join("pkg/current")).unwrap();
+
+        assert_eq!(
+            resolve_in_root(dir.path(), Path::new("pkg/current/../bin")),
+            Some(PathBuf::from("pkg/bin"))
+        );
+        assert_eq!(resolve_in_root(dir.path(), Path::new("pkg/../..")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_rejects_writes_through_symlinks() {
+        let dir = tempdir().unwrap();
+        let data = tar_bytes(|b| {
+            add_symlink(b, "here", ".");
+            add_symlink(b, "here/escape", "../outside");
+        });
+        let archive = write_archive(dir.path(), "evil.tar", &data);
+
+        let result = extract(&archive, &dir.path().join("out"));
+        assert!(matches!(result, Err(GrabError::UnsafeArchive { .. })));
+    }
+
+    #[test]
+    fn test_extract_zip_rejects_traversal() {
+        let dir = tempdir().unwrap();
+        let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        zip.start_file("../evil", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"evil").unwrap();
+        let data = zip.finish().unwrap().into_inner();
+        let archive = write_archive(dir.path(), "evil.zip", &data);
+
+        let result = extract(&archive, &dir.path().join("out"));
+        assert!(matches!(result, Err(GrabError::UnsafeArchive { .. })));
+        assert!(!dir.path().join("evil").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_zip_preserves_exec_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default().unix_permissions(0o755);
+        zip.start_file("tool/tool", options).unwrap();
+        zip.write_all(b"binary").unwrap();
+        let data = zip.finish().unwrap().into_inner();
+        let archive = write_archive(dir.path(), "tool.zip", &data);
+
+        let dest = dir.path().join("out");
+        extract(&archive, &dest).unwrap();
+        let mode = fs::metadata(dest.join("tool"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn test_extract_single_gzip_file() {
+        let dir = tempdir().unwrap();
+        let archive = write_archive(dir.path(), "tool-linux-amd64.gz", &gzip(b"binary"));
+
+        let dest = dir.path().join("out");
+        let extracted = extract(&archive, &dest).unwrap();
+        assert_eq!(extracted.files, 1);
+        assert_eq!(fs::read(dest.join("tool-linux-amd64")).unwrap(), b"binary");
+    }
+
+    #[test]
+    fn test_extract_unknown_format() {
+        let dir = tempdir().unwrap();
+        let archive = write_archive(dir.path(), "tool", b"\x7fELF binary");
+        assert!(matches!(
+            extract(&archive, &dir.path().join("out")),
+            Err(GrabError::UnsupportedArchive(_))
+        ));
+    }
+}
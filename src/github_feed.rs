@@ -0,0 +1,83 @@
+use crate::client::RequestClient;
+use crate::client::github_releases_feed_url;
+use crate::error::Result;
+use crate::github_tags::{self, Tag};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static ENTRY_ID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)<entry>.*?<id>tag:github\.com,2008:Repository/\d+/(.+?)</id>.*?</entry>")
+        .expect("Invalid regex check the feed entry pattern syntax.")
+});
+
+pub async fn latest_tag(client: &RequestClient, owner: &str, repo: &str) -> Result<Option<String>> {
+    let url = github_releases_feed_url(owner, repo);
+    let feed = client.get_text(&url).await?;
+    let tags = parse_feed_tags(&feed);
+
+    let fallback = tags.first().cloned();
+    let tags = tags.into_iter().map(|name| Tag { name }).collect();
+
+    Ok(github_tags::select_latest(tags)
+        .map(|tag| tag.name)
+        .or(fallback))
+}
+
+pub fn parse_feed_tags(feed: &str) -> Vec<String> {
+    ENTRY_ID_RE
+        .captures_iter(feed)
+        .map(|caps| unescape_xml(caps[1].trim()))
+        .collect()
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en-US">
+  <id>tag:github.com,2008:https://github.com/BurntSushi/ripgrep/releases</id>
+  <title>Release notes from ripgrep</title>
+  <entry>
+    <id>tag:github.com,2008:Repository/71342154/14.1.1</id>
+    <link rel="alternate" type="text/html" href="https://github.com/BurntSushi/ripgrep/releases/tag/14.1.1"/>
+    <title>14.1.1</title>
+  </entry>
+  <entry>
+    <id>tag:github.com,2008:Repository/71342154/14.1.0</id>
+    <link rel="alternate" type="text/html" href="https://github.com/BurntSushi/ripgrep/releases/tag/14.1.0"/>
+    <title>14.1.0</title>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_feed_tags_in_feed_order() {
+        assert_eq!(parse_feed_tags(FEED), vec!["14.1.1", "14.1.0"]);
+    }
+
+    #[test]
+    fn test_parse_feed_ignores_feed_id() {
+        let tags = parse_feed_tags(FEED);
+        assert!(!tags.iter().any(|tag| tag.contains("releases")));
+    }
+
+    #[test]
+    fn test_parse_feed_keeps_slashes_and_unescapes() {
+        let feed =
+            "<feed><entry><id>tag:github.com,2008:Repository/1/cli/v1.0&amp;x</id></entry></feed>";
+        assert_eq!(parse_feed_tags(feed), vec!["cli/v1.0&x"]);
+    }
+
+    #[test]
+    fn test_parse_empty_feed() {
+        assert!(parse_feed_tags("<feed></feed>").is_empty());
+    }
+}
@@ -1,12 +1,25 @@
+use crate::asset_pattern::{AssetMatcher, ExcludeFilter, tightened_pattern};
+use crate::asset_score;
+use crate::checksum::{self, Checksum};
 use crate::client::RequestClient;
-use crate::client::github_latest_release_url;
+use crate::client::{github_latest_release_url, github_source_archive_url};
+use crate::download::{self, Download, DownloadOptions, Verification};
 use crate::error::{GrabError, Result};
+use crate::extract;
+use crate::github_tags;
+use crate::models::{App, PatternKind, SourceArchive};
+use crate::output;
+use crate::platform::Platform;
+use crate::progress::Progress;
+use crate::signature::{self, DEFAULT_SSH_NAMESPACE, Signature};
+use crate::storage;
+use crate::ui;
 use serde::Deserialize;
-use tokio::fs::File as TokioFile;
-use tokio::io::AsyncWriteExt;
+use std::path::Path;
 
 #[derive(Debug, Deserialize)]
 pub struct Release {
+    pub tag_name: String,
     pub assets: Vec<Asset>,
 }
 
@@ -14,35 +27,463 @@ pub struct Release {
 pub struct Asset {
     pub name: String,
     pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
 }
 
-async fn get_latest_release(client: &RequestClient, owner: &str, repo: &str) -> Result<Release> {
-    let url = github_latest_release_url(owner, repo);
-    let release: Release = client.get_json(&url).await?;
-    Ok(release)
+#[derive(Debug, Clone)]
+pub struct ReleaseDownload {
+    pub tag: String,
+    pub url: String,
+    pub download: Download,
 }
 
-pub async fn download_latest_asset(
+const MAX_CHECKSUM_FILE_SIZE: u64 = 1024 * 1024;
+
+impl Release {
+    pub fn checksum_assets(&self, asset_name: &str) -> Vec<&Asset> {
+        let small = |a: &&Asset| a.size <= MAX_CHECKSUM_FILE_SIZE;
+        let per_asset = self
+            .assets
+            .iter()
+            .filter(small)
+            .filter(|a| checksum::is_per_asset_file(&a.name, asset_name));
+        let lists = self
+            .assets
+            .iter()
+            .filter(small)
+            .filter(|a| a.name != asset_name && checksum::is_checksum_list(&a.name));
+
+        per_asset.chain(lists).collect()
+    }
+}
+
+async fn get_latest_release(
     client: &RequestClient,
     owner: &str,
     repo: &str,
-    asset_pattern: &str,
-) -> Result<()> {
-    let release = get_latest_release(client, owner, repo).await?;
+) -> Result<Option<Release>> {
+    let url = github_latest_release_url(owner, repo);
+    client.get_json_optional(&url).await
+}
+
+pub async fn download_latest_asset(
+    client: &RequestClient,
+    app: &App,
+    options: &DownloadOptions,
+) -> Result<ReleaseDownload> {
+    let release = get_latest_release(client, &app.owner, &app.repo).await?;
+
+    let release = match (release, app.source) {
+        (Some(release), _) if !release.assets.is_empty() => release,
+        (Some(release), Some(source)) => {
+            return download_source_archive(client, app, &release.tag_name, source, options).await;
+        }
+        (None, Some(source)) => {
+            let tag = github_tags::latest_tag(client, &app.owner, &app.repo).await?;
+            return download_source_archive(client, app, &tag.name, source, options).await;
+        }
+        (Some(release), None) => {
+            return Err(GrabError::AssetNotFound(format!(
+                "release {} of {}/{} has no assets, use --source to download the source archive",
+                release.tag_name, app.owner, app.repo
+            )));
+        }
+        (None, None) => {
+            return Err(GrabError::ReleaseNotFound(format!(
+                "{}/{} has no GitHub releases, use --source to download the source archive of the latest tag",
+                app.owner, app.repo
+            )));
+        }
+    };
+
+    let asset = select_asset(&release.assets, app, &release.tag_name, options).await?;
+    let path = options
+        .output
+        .path_for(app, &release.tag_name, &asset.name)?;
+    check_destination(&path, options).await?;
+
+    let checksum = find_checksum(client, &release, asset).await?;
+    match &checksum {
+        None if app.require_checksum => {
+            return Err(GrabError::ChecksumMissing(format!(
+                "release {} of {}/{} publishes no checksum for {}",
+                release.tag_name, app.owner, app.repo, asset.name
+            )));
+        }
+        None => options.progress.println(format!(
+            "No checksum published for {}, skipping verification",
+            asset.name
+        )),
+        Some(_) => {}
+    }
+
+    let verification = Verification {
+        checksum,
+        signatures: find_signatures(client, &release, asset, app).await?,
+    };
+
+    let download = download_file(
+        client,
+        &asset.browser_download_url,
+        &path,
+        Some(asset.size).filter(|size| *size > 0),
+        &verification,
+        options,
+    )
+    .await?;
+
+    Ok(ReleaseDownload {
+        tag: release.tag_name.clone(),
+        url: asset.browser_download_url.clone(),
+        download,
+    })
+}
 
-    let asset = release
-        .assets
+async fn find_checksum(
+    client: &RequestClient,
+    release: &Release,
+    asset: &Asset,
+) -> Result<Option<Checksum>> {
+    for candidate in release.checksum_assets(&asset.name) {
+        let content = client.get_text(&candidate.browser_download_url).await?;
+        if let Some(checksum) = checksum::parse_checksum(&content, &candidate.name, &asset.name) {
+            return Ok(Some(checksum));
+        }
+    }
+
+    Ok(None)
+}
+
+async fn find_signatures(
+    client: &RequestClient,
+    release: &Release,
+    asset: &Asset,
+    app: &App,
+) -> Result<Vec<Signature>> {
+    let mut signatures = Vec::new();
+
+    if let Some(public_key) = &app.minisign_key {
+        signatures.push(Signature::Minisign {
+            public_key: public_key.clone(),
+            signature: fetch_signature(client, release, &asset.name, true).await?,
+        });
+    }
+
+    if let Some(public_key) = &app.ssh_key {
+        signatures.push(Signature::Ssh {
+            public_key: public_key.clone(),
+            namespace: app
+                .ssh_namespace
+                .clone()
+                .unwrap_or_else(|| DEFAULT_SSH_NAMESPACE.to_string()),
+            signature: fetch_signature(client, release, &asset.name, false).await?,
+        });
+    }
+
+    Ok(signatures)
+}
+
+async fn fetch_signature(
+    client: &RequestClient,
+    release: &Release,
+    asset_name: &str,
+    minisign: bool,
+) -> Result<String> {
+    let names = signature::signature_file_names(asset_name, minisign);
+    let signature_asset = names
+        .iter()
+        .find_map(|name| release.assets.iter().find(|a| &a.name == name))
+        .ok_or_else(|| {
+            GrabError::SignatureMissing(format!(
+                "release {} has no {} for {}",
+                release.tag_name,
+                names.join(" or "),
+                asset_name
+            ))
+        })?;
+
+    client.get_text(&signature_asset.browser_download_url).await
+}
+
+async fn select_asset<'a>(
+    assets: &'a [Asset],
+    app: &App,
+    tag: &str,
+    options: &DownloadOptions,
+) -> Result<&'a Asset> {
+    let platform = Platform::detect();
+    let defaults: &[String] = if app.no_default_excludes {
+        &[]
+    } else {
+        &options.default_excludes
+    };
+    let excludes = ExcludeFilter::new(defaults, &app.exclude)?;
+    let assets: Vec<&Asset> = assets
         .iter()
-        .find(|a| a.name.contains(asset_pattern))
-        .ok_or_else(|| GrabError::AssetNotFound(asset_pattern.to_string()))?;
+        .filter(|a| !excludes.is_excluded(&a.name))
+        .collect();
 
-    println!("Downloading: {}", asset.name);
+    let (pattern, candidates) = match &app.asset_pattern {
+        Some(pattern) => {
+            let matcher = AssetMatcher::for_release(app.pattern_kind, pattern, tag, &platform)?;
+            let candidates: Vec<&Asset> = assets
+                .into_iter()
+                .filter(|a| matcher.is_match(&a.name))
+                .collect();
+            (pattern.clone(), candidates)
+        }
+        None => {
+            let candidates = asset_score::top_matches(&assets, |a| a.name.as_str(), &platform)
+                .into_iter()
+                .copied()
+                .collect();
+            (format!("auto ({platform})"), candidates)
+        }
+    };
+
+    match candidates.as_slice() {
+        [] => Err(GrabError::AssetNotFound(format!(
+            "no asset of {}/{} matches {pattern}",
+            app.owner, app.repo
+        ))),
+        [asset] => Ok(asset),
+        _ if ui::is_interactive() => pick_asset(app, tag, &candidates, &options.progress).await,
+        _ => Err(GrabError::AmbiguousAsset {
+            pattern,
+            candidates: candidates.iter().map(|a| describe_asset(a)).collect(),
+        }),
+    }
+}
 
-    let content = client.download_bytes(&asset.browser_download_url).await?;
+async fn pick_asset<'a>(
+    app: &App,
+    tag: &str,
+    candidates: &[&'a Asset],
+    progress: &Progress,
+) -> Result<&'a Asset> {
+    let _guard = ui::lock_prompts().await;
 
-    let mut file = TokioFile::create(&asset.name).await?;
-    file.write_all(&content).await?;
+    let app = app.clone();
+    let tag = tag.to_string();
+    let items: Vec<String> = candidates.iter().map(|a| describe_asset(a)).collect();
+    let names: Vec<String> = candidates.iter().map(|a| a.name.clone()).collect();
+    let progress = progress.clone();
+    let index = tokio::task::spawn_blocking(move || {
+        progress.suspend(|| prompt_for_asset(&app, &tag, &items, &names))
+    })
+    .await??;
 
-    println!("Download completed: {}", asset.name);
+    Ok(candidates[index])
+}
+
+fn prompt_for_asset(app: &App, tag: &str, items: &[String], names: &[String]) -> Result<usize> {
+    let index = ui::select_item(
+        &format!("Several assets of {} match, choose one:", app.name),
+        items,
+    )?;
+    let chosen = &names[index];
+
+    let message = format!("Save a pattern that selects '{chosen}' for {}?", app.name);
+    if ui::confirm_action(&message)? {
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let pattern = tightened_pattern(chosen, tag, &names);
+
+        let saved = storage::modify_app(app, |stored| {
+            stored.asset_pattern = Some(pattern);
+            stored.pattern_kind = PatternKind::Regex;
+        })?;
+        if saved {
+            println!("Saved asset pattern for {}.", app.name);
+        } else {
+            println!("{} is not stored locally, pattern not saved.", app.name);
+        }
+    }
+
+    Ok(index)
+}
+
+fn describe_asset(asset: &Asset) -> String {
+    format!("{} ({})", asset.name, ui::format_size(asset.size))
+}
+
+async fn download_source_archive(
+    client: &RequestClient,
+    app: &App,
+    tag: &str,
+    source: SourceArchive,
+    options: &DownloadOptions,
+) -> Result<ReleaseDownload> {
+    if app.require_checksum {
+        return Err(GrabError::ChecksumMissing(format!(
+            "source archives of {}/{} have no published checksums",
+            app.owner, app.repo
+        )));
+    }
+
+    if app.minisign_key.is_some() || app.ssh_key.is_some() {
+        return Err(GrabError::SignatureMissing(format!(
+            "source archives of {}/{} are not signed",
+            app.owner, app.repo
+        )));
+    }
+
+    let url = github_source_archive_url(&app.owner, &app.repo, tag, source.extension());
+    let file_name = format!(
+        "{}-{}.{}",
+        app.repo,
+        output::sanitize_file_name(tag),
+        source.extension()
+    );
+    let path = options.output.path_for(app, tag, &file_name)?;
+
+    let download =
+        download_file(client, &url, &path, None, &Verification::default(), options).await?;
+
+    Ok(ReleaseDownload {
+        tag: tag.to_string(),
+        url,
+        download,
+    })
+}
+
+async fn check_destination(path: &Path, options: &DownloadOptions) -> Result<()> {
+    if !options.force && tokio::fs::try_exists(path).await? {
+        return Err(GrabError::FileExists(path.display().to_string()));
+    }
     Ok(())
 }
+
+async fn download_file(
+    client: &RequestClient,
+    url: &str,
+    path: &Path,
+    expected_size: Option<u64>,
+    verification: &Verification,
+    options: &DownloadOptions,
+) -> Result<Download> {
+    options
+        .progress
+        .println(format!("Downloading: {}", path.display()));
+
+    check_destination(path, options).await?;
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+
+    let cached = match &options.cache {
+        Some(cache) => {
+            cache
+                .fetch(url, path, expected_size, verification, options.force)
+                .await?
+        }
+        None => None,
+    };
+
+    let download = match cached {
+        Some(download) => {
+            print_verified(path, verification, &options.progress);
+            options.progress.println(format!(
+                "Using cached copy of {} ({}, sha256: {})",
+                path.display(),
+                ui::format_size(download.size),
+                download.sha256
+            ));
+            download
+        }
+        None => {
+            let download =
+                download::download_to_file(client, url, path, expected_size, verification, options)
+                    .await?;
+
+            print_verified(path, verification, &options.progress);
+            options.progress.println(format!(
+                "Download completed: {} ({}, sha256: {})",
+                path.display(),
+                ui::format_size(download.size),
+                download.sha256
+            ));
+
+            if let Some(cache) = &options.cache
+                && let Err(e) = cache.insert(url, &download).await
+            {
+                options
+                    .progress
+                    .println(format!("Could not cache {}: {e}", path.display()));
+            }
+            download
+        }
+    };
+
+    if options.extract {
+        extract_download(&download.path, options).await?;
+    }
+
+    Ok(download)
+}
+
+async fn extract_download(path: &Path, options: &DownloadOptions) -> Result<()> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = extract::archive_stem(&name);
+    let dest = if stem == name {
+        path.with_file_name(format!("{name}-extracted"))
+    } else {
+        path.with_file_name(stem)
+    };
+
+    if tokio::fs::try_exists(&dest).await? {
+        if !options.force {
+            return Err(GrabError::FileExists(dest.display().to_string()));
+        }
+        if tokio::fs::metadata(&dest).await?.is_dir() {
+            tokio::fs::remove_dir_all(&dest).await?;
+        } else {
+            tokio::fs::remove_file(&dest).await?;
+        }
+    }
+
+    let archive = path.to_path_buf();
+    let target = dest.clone();
+    let extracted =
+        tokio::task::spawn_blocking(move || extract::extract(&archive, &target)).await?;
+
+    match extracted {
+        Ok(extracted) => {
+            options.progress.println(format!(
+                "Extracted {} files from {} into {}",
+                extracted.files,
+                name,
+                extracted.dir.display()
+            ));
+            Ok(())
+        }
+        Err(GrabError::UnsupportedArchive(_)) => {
+            options
+                .progress
+                .println(format!("{name} is not an archive, nothing to extract"));
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn print_verified(path: &Path, verification: &Verification, progress: &Progress) {
+    if let Some(checksum) = &verification.checksum {
+        progress.println(format!(
+            "Verified {} checksum of {}",
+            checksum.algorithm_label(),
+            path.display()
+        ));
+    }
+    for signature in &verification.signatures {
+        progress.println(format!(
+            "Verified {} signature of {}",
+            signature.kind(),
+            path.display()
+        ));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::client::RequestClient;
+use crate::client::github_tags_url;
+use crate::error::{GrabError, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use semver::Version as SemVersion;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tag {
+    pub name: String,
+}
+
+const MAX_TAG_PAGES: usize = 5;
+
+static TAG_VERSION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)(?:\.(\d+))?(?:\.(\d+))?([-+][0-9A-Za-z.+-]*)?$")
+        .expect("Invalid regex check the tag pattern syntax.")
+});
+
+pub async fn latest_tag(client: &RequestClient, owner: &str, repo: &str) -> Result<Tag> {
+    let url = github_tags_url(owner, repo);
+    let tags: Vec<Tag> = client.get_json_pages(&url, MAX_TAG_PAGES).await?;
+
+    select_latest(tags)
+        .ok_or_else(|| GrabError::ReleaseNotFound(format!("no releases or tags in {owner}/{repo}")))
+}
+
+pub fn select_latest(tags: Vec<Tag>) -> Option<Tag> {
+    let mut versioned: Vec<(SemVersion, Tag)> = tags
+        .into_iter()
+        .filter_map(|tag| parse_tag_version(&tag.name).map(|version| (version, tag)))
+        .collect();
+
+    versioned.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let latest_stable = versioned
+        .iter()
+        .rposition(|(version, _)| version.pre.is_empty());
+
+    match latest_stable {
+        Some(index) => Some(versioned.swap_remove(index).1),
+        None => versioned.pop().map(|(_, tag)| tag),
+    }
+}
+
+pub fn parse_tag_version(tag: &str) -> Option<SemVersion> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+    let candidate = &tag[start..];
+
+    if let Ok(version) = SemVersion::parse(candidate) {
+        return Some(version);
+    }
+
+    let caps = TAG_VERSION_RE.captures(candidate)?;
+
+    let part = |i: usize| caps.get(i).map_or("0", |m| m.as_str());
+    let suffix = caps.get(4).map_or("", |m| m.as_str());
+
+    SemVersion::parse(&format!("{}.{}.{}{}", part(1), part(2), part(3), suffix)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<Tag> {
+        names
+            .iter()
+            .map(|name| Tag {
+                name: name.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_plain_semver() {
+        assert_eq!(parse_tag_version("1.2.3"), SemVersion::parse("1.2.3").ok());
+    }
+
+    #[test]
+    fn test_parse_strips_prefix() {
+        assert_eq!(parse_tag_version("v1.2.3"), SemVersion::parse("1.2.3").ok());
+        assert_eq!(
+            parse_tag_version("release-2.0.1"),
+            SemVersion::parse("2.0.1").ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_pads_missing_components() {
+        assert_eq!(parse_tag_version("v1.4"), SemVersion::parse("1.4.0").ok());
+        assert_eq!(parse_tag_version("v7"), SemVersion::parse("7.0.0").ok());
+    }
+
+    #[test]
+    fn test_parse_keeps_prerelease() {
+        let version = parse_tag_version("v1.4-rc1").unwrap();
+        assert_eq!(version.pre.as_str(), "rc1");
+    }
+
+    #[test]
+    fn test_parse_rejects_non_version_tags() {
+        assert!(parse_tag_version("nightly").is_none());
+        assert!(parse_tag_version("1.2.3.4.5").is_none());
+    }
+
+    #[test]
+    fn test_select_latest_uses_semver_order() {
+        let latest = select_latest(tags(&["v1.9.0", "v1.10.0", "v1.2.0"])).unwrap();
+        assert_eq!(latest.name, "v1.10.0");
+    }
+
+    #[test]
+    fn test_select_latest_prefers_stable_over_prerelease() {
+        let latest = select_latest(tags(&["v2.0.0-rc1", "v1.9.0", "nightly"])).unwrap();
+        assert_eq!(latest.name, "v1.9.0");
+    }
+
+    #[test]
+    fn test_select_latest_falls_back_to_prerelease() {
+        let latest = select_latest(tags(&["v0.1.0-alpha", "v0.1.0-beta"])).unwrap();
+        assert_eq!(latest.name, "v0.1.0-beta");
+    }
+
+    #[test]
+    fn test_select_latest_without_versions_returns_none() {
+        assert!(select_latest(tags(&["nightly", "latest"])).is_none());
+        assert!(select_latest(Vec::new()).is_none());
+    }
+}
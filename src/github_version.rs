@@ -1,9 +1,19 @@
 use crate::client::RequestClient;
-use crate::client::github_latest_release_url;
+use crate::client::{github_latest_release_url, github_release_html_url, github_tag_html_url};
 use crate::error::Result;
+use crate::github_feed;
+use crate::github_tags;
 use crate::models::App;
+use clap::ValueEnum;
 use serde::Deserialize;
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Discovery {
+    #[default]
+    Api,
+    Atom,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Version {
     pub tag_name: String,
@@ -11,9 +21,37 @@ pub struct Version {
 }
 
 impl Version {
+    pub async fn discover(
+        app: &App,
+        client: &RequestClient,
+        discovery: Discovery,
+    ) -> Result<Version> {
+        match discovery {
+            Discovery::Api => Self::latest(app, client).await,
+            Discovery::Atom => Self::latest_from_feed(app, client).await,
+        }
+    }
+
     pub async fn latest(app: &App, client: &RequestClient) -> Result<Version> {
         let url = github_latest_release_url(&app.owner, &app.repo);
-        let version: Version = client.get_json(&url).await?;
-        Ok(version)
+        if let Some(version) = client.get_json_optional::<Version>(&url).await? {
+            return Ok(version);
+        }
+
+        let tag = github_tags::latest_tag(client, &app.owner, &app.repo).await?;
+        Ok(Version {
+            html_url: github_tag_html_url(&app.owner, &app.repo, &tag.name),
+            tag_name: tag.name,
+        })
+    }
+
+    pub async fn latest_from_feed(app: &App, client: &RequestClient) -> Result<Version> {
+        match github_feed::latest_tag(client, &app.owner, &app.repo).await? {
+            Some(tag_name) => Ok(Version {
+                html_url: github_release_html_url(&app.owner, &app.repo, &tag_name),
+                tag_name,
+            }),
+            None => Self::latest(app, client).await,
+        }
     }
 }
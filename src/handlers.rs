@@ -1,6 +1,12 @@
-use crate::app;
-use crate::cli::{AddArgs, CheckArgs, RemoteArgs, RemoveArgs, SearchArgs};
+use crate::app::{self, UpdateAction};
+use crate::cache::{self, Cache};
+use crate::cli::{
+    AddArgs, CacheArgs, CacheCommand, CheckArgs, DownloadArgs, InstallArgs, RemoteArgs, RemoveArgs,
+    RollbackArgs, SearchArgs, UninstallArgs,
+};
+use crate::config;
 use crate::error::{GrabError, Result};
+use crate::install::InstallOptions;
 use crate::models::App;
 use crate::ui;
 
@@ -18,11 +24,130 @@ pub fn handle_list() -> Result<()> {
 }
 
 pub async fn handle_check(args: CheckArgs) -> Result<()> {
-    app::check_apps(args.download).await
+    let config = config::load_config()?;
+    let action = if args.install {
+        UpdateAction::Install(InstallOptions {
+            force: args.transfer.force,
+            ..InstallOptions::new(args.bin_dir, &config)?
+        })
+    } else if args.download {
+        UpdateAction::Download
+    } else {
+        UpdateAction::Report
+    };
+
+    app::check_apps(
+        action,
+        args.discovery,
+        args.transfer.download_options(&config)?,
+    )
+    .await
+}
+
+pub async fn handle_download(args: DownloadArgs) -> Result<()> {
+    let config = config::load_config()?;
+    app::download_apps(args.transfer.download_options(&config)?).await
+}
+
+pub async fn handle_install(args: InstallArgs) -> Result<()> {
+    let config = config::load_config()?;
+    app::install_apps(
+        args.names,
+        InstallOptions {
+            force: args.transfer.force,
+            ..InstallOptions::new(args.bin_dir, &config)?
+        },
+        args.transfer.download_options(&config)?,
+    )
+    .await
+}
+
+pub fn handle_rollback(args: RollbackArgs) -> Result<()> {
+    let config = config::load_config()?;
+    let install = InstallOptions {
+        force: args.force,
+        ..InstallOptions::new(None, &config)?
+    };
+
+    let (previous, current) = app::rollback_app(&args.name, args.to.as_deref(), &install)?;
+    println!(
+        "Rolled back {} from {} to {} ({})",
+        args.name,
+        previous.version,
+        current.version,
+        current.dir.display()
+    );
+    Ok(())
+}
+
+pub fn handle_uninstall(args: UninstallArgs) -> Result<()> {
+    let result = app::uninstall_app(&args.name, args.force)?;
+
+    for path in &result.modified {
+        println!(
+            "Warning: {} was modified since installation, left in place (use --force to remove it)",
+            path.display()
+        );
+    }
+    println!(
+        "Uninstalled {}: removed {} file(s).",
+        args.name,
+        result.removed.len()
+    );
+    Ok(())
 }
 
-pub async fn handle_download() -> Result<()> {
-    app::download_apps().await
+pub fn handle_cache(args: CacheArgs) -> Result<()> {
+    let cache = Cache::open(&config::load_config()?)?;
+
+    match args.command {
+        CacheCommand::List => {
+            let mut entries = cache.entries()?;
+            if entries.is_empty() {
+                println!("The cache at {} is empty.", cache.dir().display());
+                return Ok(());
+            }
+
+            entries.sort_by_key(|e| std::cmp::Reverse(e.last_used));
+            let now = cache::now();
+            for entry in &entries {
+                println!(
+                    "{} ({}, used {}) sha256:{}\n  {}",
+                    entry.name,
+                    ui::format_size(entry.size),
+                    ui::format_age(now.saturating_sub(entry.last_used)),
+                    &entry.sha256[..12.min(entry.sha256.len())],
+                    entry.url
+                );
+            }
+
+            let total: u64 = entries.iter().map(|e| e.size).sum();
+            println!(
+                "\n{} entries, {} of {} in {}",
+                entries.len(),
+                ui::format_size(total),
+                ui::format_size(cache.max_size()),
+                cache.dir().display()
+            );
+        }
+        CacheCommand::Prune(prune) => {
+            let max_size = prune.max_size.unwrap_or(cache.max_size());
+            let max_age = prune.older_than.map(|days| days * 24 * 60 * 60);
+            let evicted = cache.prune(max_size, max_age)?;
+            let freed: u64 = evicted.iter().map(|e| e.size).sum();
+            println!(
+                "Pruned {} entries, freed {}.",
+                evicted.len(),
+                ui::format_size(freed)
+            );
+        }
+        CacheCommand::Clear => {
+            let removed = cache.clear()?;
+            println!("Removed {removed} cached downloads.");
+        }
+    }
+
+    Ok(())
 }
 
 pub fn handle_add(args: AddArgs) -> Result<()> {
@@ -42,7 +167,8 @@ pub async fn handle_remote_download(args: RemoteArgs) -> Result<()> {
         GrabError::InvalidInput("Please provide a non-empty remote file.".to_string())
     })?;
 
-    app::download_remote_apps(file).await
+    let config = config::load_config()?;
+    app::download_remote_apps(file, args.transfer.download_options(&config)?).await
 }
 
 pub fn handle_remove(args: RemoveArgs) -> Result<()> {
@@ -52,6 +178,12 @@ pub fn handle_remove(args: RemoveArgs) -> Result<()> {
     match app_to_delete {
         Some((_, app)) => {
             println!("You are about to delete:\n  Name: {}", app.name);
+            if !app.installs.is_empty() {
+                println!(
+                    "{0} has installed files, run `grab uninstall {0}` first to delete them.",
+                    app.name
+                );
+            }
             if ui::confirm_action("Are you sure you want to delete this app?")? {
                 app::remove_app(args.index)?;
                 println!("App deleted successfully!");
@@ -92,11 +224,27 @@ fn collect_app_interactively(args: AddArgs) -> Result<App> {
     let name = get_or_prompt(args.name, "App name")?;
     let owner = get_or_prompt(args.owner, "Repo owner")?;
     let repo = get_or_prompt(args.repo, "Repo name")?;
-    let asset_pattern = get_or_prompt(args.asset_pattern, "Asset pattern")?;
+    let asset_pattern = match args.asset_pattern {
+        Some(val) => val,
+        None => ui::prompt_for_input("Asset pattern (empty to auto-detect)", false)?,
+    };
     let version_flag = get_or_prompt(args.version_flag, "Version flag")?;
 
     println!();
-    App::new(name, owner, repo, asset_pattern, version_flag)
+    let mut app = App::new(name, owner, repo, asset_pattern, version_flag)?;
+    app.pattern_kind = args.pattern_kind;
+    app.exclude = args.exclude;
+    app.no_default_excludes = args.no_default_excludes;
+    app.source = args.source;
+    app.dest = args.dest;
+    app.require_checksum = args.require_checksum;
+    app.minisign_key = args.minisign_key;
+    app.ssh_key = args.ssh_key;
+    app.ssh_namespace = args.ssh_namespace;
+    app.bin = args.bin;
+    app.bin_path = args.bin_path;
+    app.install = args.install_file;
+    Ok(app)
 }
 
 fn get_or_prompt(field: Option<String>, label: &str) -> Result<String> {
@@ -0,0 +1,1172 @@
+use crate::asset_pattern::glob_to_regex;
+use crate::checksum::HashAlgorithm;
+use crate::client::RequestClient;
+use crate::config::Config;
+use crate::download::DownloadOptions;
+use crate::error::{GrabError, Result};
+use crate::extract;
+use crate::github_release;
+use crate::models::{App, InstallRecord, InstallTarget, InstalledFile};
+use crate::output::{OutputOptions, expand_home, safe_file_name, sanitize_file_name};
+use crate::storage;
+use regex::Regex;
+use std::env::consts::EXE_SUFFIX;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_BIN_DIR: &str = "~/.local/bin";
+pub const DEFAULT_SHARE_DIR: &str = "~/.local/share";
+const CURRENT_LINK: &str = "current";
+
+#[derive(Debug, Clone)]
+pub struct InstallOptions {
+    pub bin_dir: PathBuf,
+    pub share_dir: PathBuf,
+    pub apps_dir: PathBuf,
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFile {
+    pub source: PathBuf,
+    pub target: InstallTarget,
+    pub path: PathBuf,
+    pub executable: bool,
+}
+
+impl InstallOptions {
+    pub fn new(bin_dir: Option<PathBuf>, config: &Config) -> Result<Self> {
+        let bin_dir = bin_dir
+            .or_else(|| config.bin_dir.clone())
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_BIN_DIR));
+        let share_dir = config
+            .share_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_SHARE_DIR));
+
+        let share_dir = std::path::absolute(expand_home(&share_dir))?;
+
+        Ok(Self {
+            bin_dir: std::path::absolute(expand_home(&bin_dir))?,
+            apps_dir: share_dir.join("grab").join("apps"),
+            share_dir,
+            force: false,
+        })
+    }
+
+    pub fn app_dir(&self, app: &App) -> PathBuf {
+        self.apps_dir.join(sanitize_file_name(&app.name))
+    }
+
+    pub fn target_dir(&self, target: InstallTarget, app: &App) -> PathBuf {
+        match target {
+            InstallTarget::Bin => self.bin_dir.clone(),
+            InstallTarget::Man1 => self.share_dir.join("man").join("man1"),
+            InstallTarget::Bash => self.share_dir.join("bash-completion").join("completions"),
+            InstallTarget::Zsh => self.share_dir.join("zsh").join("site-functions"),
+            InstallTarget::Fish => self.share_dir.join("fish").join("vendor_completions.d"),
+            InstallTarget::Share => self.share_dir.join(sanitize_file_name(&app.name)),
+        }
+    }
+
+    pub fn link_path(&self, app: &App, target: InstallTarget, path: &Path) -> PathBuf {
+        self.target_dir(target, app).join(path)
+    }
+}
+
+struct PathMatcher {
+    regex: Regex,
+    full_path: bool,
+}
+
+impl PathMatcher {
+    fn new(glob: &str) -> Result<Self> {
+        Ok(Self {
+            regex: Regex::new(&glob_to_regex(glob))?,
+            full_path: glob.contains('/'),
+        })
+    }
+
+    fn is_match(&self, relative: &Path) -> bool {
+        if self.full_path {
+            self.regex.is_match(&slash_path(relative))
+        } else {
+            self.regex.is_match(&file_name(relative))
+        }
+    }
+}
+
+pub async fn install_latest(
+    client: &RequestClient,
+    app: &App,
+    install: &InstallOptions,
+    options: &DownloadOptions,
+) -> Result<InstallRecord> {
+    safe_file_name(app.bin_name())?;
+    let staging = tempfile::Builder::new()
+        .prefix(".grab-install-")
+        .tempdir()?;
+    let fetch_options = DownloadOptions {
+        output: OutputOptions {
+            dir: Some(staging.path().to_path_buf()),
+            ..OutputOptions::default()
+        },
+        force: true,
+        extract: false,
+        ..options.clone()
+    };
+    let release = github_release::download_latest_asset(client, app, &fetch_options).await?;
+
+    let archive = release.download.path.clone();
+    let contents = staging.path().join("contents");
+    let task_app = app.clone();
+    let task_install = install.clone();
+    let tag = release.tag.clone();
+    let asset_url = release.url.clone();
+    let record = tokio::task::spawn_blocking(move || -> Result<InstallRecord> {
+        let planned = plan_asset(archive, &contents, &task_app)?;
+        let record = stage_version(&planned, &tag, &asset_url, &task_app, &task_install)?;
+        activate(&record, task_app.active_install(), &task_install)?;
+        Ok(record)
+    })
+    .await??;
+
+    storage::modify_app(app, |stored| {
+        stored.installs.retain(|r| r.version != record.version);
+        stored.installs.push(record.clone());
+        stored.active_version = Some(record.version.clone());
+    })?;
+
+    let listing: Vec<String> = record
+        .files
+        .iter()
+        .map(|file| format!("  {}", file.link.display()))
+        .collect();
+    options.progress.println(format!(
+        "Installed {} {} into {}:\n{}",
+        app.name,
+        record.version,
+        record.dir.display(),
+        listing.join("\n")
+    ));
+
+    Ok(record)
+}
+
+fn plan_asset(asset: PathBuf, contents: &Path, app: &App) -> Result<Vec<PlannedFile>> {
+    match extract::extract(&asset, contents) {
+        Ok(extracted) => plan_install(&extracted.dir, app),
+        Err(GrabError::UnsupportedArchive(name)) if !app.install.is_empty() => {
+            Err(GrabError::InvalidInput(format!(
+                "{name} is not an archive, so the install rules of {} cannot be applied",
+                app.name
+            )))
+        }
+        Err(GrabError::UnsupportedArchive(_)) => Ok(vec![primary_binary(app, asset)]),
+        Err(e) => Err(e),
+    }
+}
+
+fn primary_binary(app: &App, source: PathBuf) -> PlannedFile {
+    PlannedFile {
+        source,
+        target: InstallTarget::Bin,
+        path: PathBuf::from(binary_file_name(app.bin_name())),
+        executable: true,
+    }
+}
+
+pub fn stage_version(
+    planned: &[PlannedFile],
+    tag: &str,
+    asset_url: &str,
+    app: &App,
+    install: &InstallOptions,
+) -> Result<InstallRecord> {
+    let app_dir = install.app_dir(app);
+    fs::create_dir_all(&app_dir)?;
+
+    let staging = tempfile::Builder::new()
+        .prefix(".grab-")
+        .tempdir_in(&app_dir)?;
+    let mut files = Vec::with_capacity(planned.len());
+    for file in planned {
+        let dest = staging
+            .path()
+            .join(file.target.to_string())
+            .join(&file.path);
+        files.push(InstalledFile {
+            link: install.link_path(app, file.target, &file.path),
+            target: file.target,
+            path: file.path.clone(),
+            sha256: install_file(&file.source, &dest, file.executable)?,
+        });
+    }
+
+    let version = sanitize_file_name(tag);
+    let dir = app_dir.join(&version);
+    let replaced = app_dir.join(format!(".grab-replaced-{version}"));
+    if fs::symlink_metadata(&replaced).is_ok() {
+        fs::remove_dir_all(&replaced)?;
+    }
+    let replacing = fs::symlink_metadata(&dir).is_ok();
+    if replacing {
+        fs::rename(&dir, &replaced)?;
+    }
+    let staged = staging.keep();
+    if let Err(e) = fs::rename(&staged, &dir) {
+        let _ = fs::remove_dir_all(&staged);
+        if replacing {
+            let _ = fs::rename(&replaced, &dir);
+        }
+        return Err(e.into());
+    }
+    if replacing {
+        fs::remove_dir_all(&replaced)?;
+    }
+
+    Ok(InstallRecord {
+        version,
+        dir,
+        asset_url: asset_url.to_string(),
+        installed_at: crate::cache::now(),
+        files,
+    })
+}
+
+pub fn activate(
+    record: &InstallRecord,
+    previous: Option<&InstallRecord>,
+    install: &InstallOptions,
+) -> Result<()> {
+    let app_dir = record.dir.parent().ok_or_else(|| {
+        GrabError::InvalidInput(format!("{} has no parent", record.dir.display()))
+    })?;
+    let current = app_dir.join(CURRENT_LINK);
+
+    for file in &record.files {
+        if !is_managed_link(&file.link, app_dir) && fs::symlink_metadata(&file.link).is_ok() {
+            if !install.force {
+                return Err(GrabError::FileExists(file.link.display().to_string()));
+            }
+            if fs::metadata(&file.link).is_ok_and(|m| m.is_dir()) {
+                return Err(GrabError::InvalidInput(format!(
+                    "{} is a directory",
+                    file.link.display()
+                )));
+            }
+        }
+    }
+
+    for file in &record.files {
+        let target = current.join(file.target.to_string()).join(&file.path);
+        replace_symlink(&target, &file.link)?;
+    }
+
+    let version_dir = record
+        .dir
+        .file_name()
+        .ok_or_else(|| GrabError::InvalidInput(format!("{} has no name", record.dir.display())))?;
+    replace_symlink(Path::new(version_dir), &current)?;
+
+    if let Some(previous) = previous {
+        for file in &previous.files {
+            let still_used = record.files.iter().any(|f| f.link == file.link);
+            if !still_used && is_managed_link(&file.link, app_dir) {
+                fs::remove_file(&file.link)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn rollback_target<'a>(app: &'a App, version: Option<&str>) -> Result<&'a InstallRecord> {
+    let active = app.active_install().ok_or_else(|| {
+        GrabError::NotFound(format!("{} has not been installed with grab", app.name))
+    })?;
+    let available =
+        |record: &&InstallRecord| record.version != active.version && record.dir.is_dir();
+
+    let target = match version {
+        Some(version) => {
+            let version = sanitize_file_name(version);
+            app.installs
+                .iter()
+                .filter(available)
+                .find(|record| record.version == version)
+        }
+        None => {
+            let position = app
+                .installs
+                .iter()
+                .position(|record| record.version == active.version)
+                .unwrap_or_default();
+            app.installs[..position].iter().rev().find(available)
+        }
+    };
+
+    target.ok_or_else(|| {
+        GrabError::NotFound(format!(
+            "no previous install of {} {}",
+            app.name,
+            version.map_or("to roll back to".to_string(), |v| format!("matches {v}"))
+        ))
+    })
+}
+
+pub fn rollback(
+    app: &App,
+    version: Option<&str>,
+    install: &InstallOptions,
+) -> Result<InstallRecord> {
+    let target = rollback_target(app, version)?;
+    activate(target, app.active_install(), install)?;
+    Ok(target.clone())
+}
+
+#[derive(Debug, Default)]
+pub struct Uninstalled {
+    pub removed: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+}
+
+pub fn uninstall(app: &App, force: bool) -> Result<Uninstalled> {
+    let mut result = Uninstalled::default();
+
+    for record in &app.installs {
+        let app_dir = record.dir.parent().unwrap_or(&record.dir);
+
+        for file in &record.files {
+            if is_managed_link(&file.link, app_dir) {
+                fs::remove_file(&file.link)?;
+                result.removed.push(file.link.clone());
+            } else if fs::symlink_metadata(&file.link).is_ok()
+                && !result.modified.contains(&file.link)
+                && !result.removed.contains(&file.link)
+            {
+                result.modified.push(file.link.clone());
+            }
+
+            let path = record.file_path(file);
+            if fs::symlink_metadata(&path).is_err() {
+                continue;
+            }
+            let changed = !file.sha256.is_empty() && sha256_file(&path)? != file.sha256;
+            if changed && !force {
+                result.modified.push(path);
+            } else {
+                fs::remove_file(&path)?;
+                result.removed.push(path);
+            }
+        }
+
+        remove_empty_dirs(&record.dir)?;
+        let current = app_dir.join(CURRENT_LINK);
+        if fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink())
+            && !current.exists()
+        {
+            fs::remove_file(&current)?;
+        }
+        remove_empty_dirs(app_dir)?;
+    }
+
+    Ok(result)
+}
+
+fn remove_empty_dirs(dir: &Path) -> Result<()> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_empty_dirs(&entry.path())?;
+        }
+    }
+
+    if fs::read_dir(dir)?.next().is_none() {
+        fs::remove_dir(dir)?;
+    }
+    Ok(())
+}
+
+pub fn is_managed_link(link: &Path, app_dir: &Path) -> bool {
+    fs::read_link(link).is_ok_and(|target| target.starts_with(app_dir))
+}
+
+fn replace_symlink(target: &Path, link: &Path) -> Result<()> {
+    let dir = link
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let temp = dir.join(format!(
+        ".{}.grab-{}.tmp",
+        file_name(link),
+        std::process::id()
+    ));
+    if fs::symlink_metadata(&temp).is_ok() {
+        fs::remove_file(&temp)?;
+    }
+    symlink(target, &temp)?;
+    fs::rename(&temp, link)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, link)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> Result<()> {
+    let resolved = link
+        .parent()
+        .map_or(target.to_path_buf(), |dir| dir.join(target));
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)?;
+    } else {
+        std::os::windows::fs::symlink_file(target, link)?;
+    }
+    Ok(())
+}
+
+pub fn plan_install(root: &Path, app: &App) -> Result<Vec<PlannedFile>> {
+    let mut planned = Vec::new();
+
+    if !app
+        .install
+        .iter()
+        .any(|rule| rule.target == InstallTarget::Bin)
+    {
+        planned.push(primary_binary(app, find_binary(root, app)?));
+    }
+
+    for rule in &app.install {
+        let matches = matching_entries(root, &PathMatcher::new(&rule.source)?)?;
+        if matches.is_empty() {
+            return Err(GrabError::NotFound(format!(
+                "no file in the release of {} matches install rule '{rule}'",
+                app.name
+            )));
+        }
+
+        let force_executable = rule.target == InstallTarget::Bin;
+        for relative in matches {
+            let source = root.join(&relative);
+            let path = PathBuf::from(file_name(&relative));
+            let files = if fs::symlink_metadata(&source)?.is_dir() {
+                list_files(&source)?
+                    .into_iter()
+                    .map(|file| (source.join(&file), path.join(file)))
+                    .collect()
+            } else {
+                vec![(source, path)]
+            };
+
+            for (source, path) in files {
+                planned.push(PlannedFile {
+                    executable: force_executable || is_executable(&source)?,
+                    source,
+                    target: rule.target,
+                    path,
+                });
+            }
+        }
+    }
+
+    check_conflicts(&planned)?;
+    Ok(planned)
+}
+
+fn check_conflicts(planned: &[PlannedFile]) -> Result<()> {
+    for (i, file) in planned.iter().enumerate() {
+        let same_dest =
+            |other: &&PlannedFile| other.target == file.target && other.path == file.path;
+        if let Some(other) = planned[..i].iter().find(same_dest) {
+            return Err(GrabError::InvalidInput(format!(
+                "both {} and {} would be installed as {} {}",
+                other.source.display(),
+                file.source.display(),
+                file.target,
+                file.path.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn binary_file_name(bin: &str) -> String {
+    if EXE_SUFFIX.is_empty() || bin.ends_with(EXE_SUFFIX) {
+        bin.to_string()
+    } else {
+        format!("{bin}{EXE_SUFFIX}")
+    }
+}
+
+pub fn find_binary(root: &Path, app: &App) -> Result<PathBuf> {
+    let pattern = app.bin_path.as_deref().map(PathMatcher::new).transpose()?;
+    let files = list_files(root)?;
+
+    let mut matches: Vec<&PathBuf> = files
+        .iter()
+        .filter(|file| match &pattern {
+            Some(pattern) => pattern.is_match(file),
+            None => {
+                let name = file_name(file);
+                name == app.bin_name() || name == binary_file_name(app.bin_name())
+            }
+        })
+        .collect();
+
+    if matches.is_empty() && pattern.is_none() && files.len() == 1 {
+        matches.extend(&files);
+    }
+
+    matches.sort_by_key(|file| (file.components().count(), file.to_path_buf()));
+    match matches.first() {
+        Some(file) => Ok(root.join(file)),
+        None => Err(GrabError::BinaryNotFound(format!(
+            "no file in the release of {} matches '{}', set the binary with --bin or --bin-path",
+            app.name,
+            app.bin_path.as_deref().unwrap_or(app.bin_name())
+        ))),
+    }
+}
+
+fn list_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+
+    while let Some(relative) = pending.pop() {
+        for entry in fs::read_dir(root.join(&relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn matching_entries(root: &Path, matcher: &PathMatcher) -> Result<Vec<PathBuf>> {
+    let mut matches = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+
+    while let Some(relative) = pending.pop() {
+        for entry in fs::read_dir(root.join(&relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if file_type.is_symlink() {
+                continue;
+            } else if matcher.is_match(&path) {
+                matches.push(path);
+            } else if file_type.is_dir() {
+                pending.push(path);
+            }
+        }
+    }
+
+    matches.sort();
+    Ok(matches)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn install_file(source: &Path, dest: &Path, executable: bool) -> Result<String> {
+    let dir = dest
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let mut temp = tempfile::Builder::new()
+        .prefix(".grab-")
+        .suffix(".tmp")
+        .tempfile_in(dir)?;
+    io::copy(&mut File::open(source)?, temp.as_file_mut())?;
+    temp.as_file().sync_all()?;
+    set_mode(temp.path(), executable)?;
+    let sha256 = sha256_file(temp.path())?;
+    temp.persist(dest).map_err(|e| GrabError::Io(e.error))?;
+    Ok(sha256)
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut reader = File::open(path)?;
+    let mut hasher = HashAlgorithm::Sha256.hasher();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finalize_hex());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, executable: bool) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = if executable { 0o755 } else { 0o644 };
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _executable: bool) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+
+    Ok(fs::metadata(path)?.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> Result<bool> {
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn app(bin: Option<&str>, bin_path: Option<&str>) -> App {
+        let mut app = App::new("ripgrep", "BurntSushi", "ripgrep", "", "--version").unwrap();
+        app.bin = bin.map(str::to_string);
+        app.bin_path = bin_path.map(str::to_string);
+        app
+    }
+
+    fn touch(root: &Path, path: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"binary").unwrap();
+    }
+
+    #[test]
+    fn test_find_binary_by_name_prefers_shallowest() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "doc/rg.1");
+        touch(dir.path(), "rg");
+        touch(dir.path(), "complete/rg");
+
+        let found = find_binary(dir.path(), &app(Some("rg"), None)).unwrap();
+        assert_eq!(found, dir.path().join("rg"));
+    }
+
+    #[test]
+    fn test_find_binary_defaults_to_app_name() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "README.md");
+        touch(dir.path(), "bin/ripgrep");
+
+        let found = find_binary(dir.path(), &app(None, None)).unwrap();
+        assert_eq!(found, dir.path().join("bin/ripgrep"));
+    }
+
+    #[test]
+    fn test_find_binary_by_pattern() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "tool-linux-amd64");
+        touch(dir.path(), "extras/bin/rg");
+        touch(dir.path(), "bin/rg");
+
+        let by_name = find_binary(dir.path(), &app(Some("rg"), Some("tool-*"))).unwrap();
+        assert_eq!(by_name, dir.path().join("tool-linux-amd64"));
+
+        let by_path = find_binary(dir.path(), &app(Some("rg"), Some("extras/*/rg"))).unwrap();
+        assert_eq!(by_path, dir.path().join("extras/bin/rg"));
+    }
+
+    #[test]
+    fn test_find_binary_uses_only_file() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "tool-linux-amd64");
+
+        let found = find_binary(dir.path(), &app(None, None)).unwrap();
+        assert_eq!(found, dir.path().join("tool-linux-amd64"));
+    }
+
+    #[test]
+    fn test_find_binary_not_found() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "README.md");
+        touch(dir.path(), "LICENSE");
+
+        let result = find_binary(dir.path(), &app(None, None));
+        assert!(matches!(result, Err(GrabError::BinaryNotFound(_))));
+
+        let result = find_binary(dir.path(), &app(None, Some("bin/*")));
+        assert!(matches!(result, Err(GrabError::BinaryNotFound(_))));
+    }
+
+    #[test]
+    fn test_install_file_replaces_existing_binary() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("rg");
+        fs::write(&source, b"new").unwrap();
+        let dest = dir.path().join("bin/rg");
+        fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        fs::write(&dest, b"old").unwrap();
+
+        install_file(&source, &dest, true).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"new");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&dest).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+
+        let leftovers = fs::read_dir(dest.parent().unwrap()).unwrap().count();
+        assert_eq!(leftovers, 1);
+    }
+
+    #[test]
+    fn test_install_options_precedence() {
+        let config = Config {
+            bin_dir: Some(PathBuf::from("/opt/bin")),
+            ..Config::default()
+        };
+
+        let cli = InstallOptions::new(Some(PathBuf::from("/usr/local/bin")), &config).unwrap();
+        assert_eq!(cli.bin_dir, PathBuf::from("/usr/local/bin"));
+        assert_eq!(
+            InstallOptions::new(None, &config).unwrap().bin_dir,
+            PathBuf::from("/opt/bin")
+        );
+        assert!(
+            InstallOptions::new(None, &Config::default())
+                .unwrap()
+                .bin_dir
+                .ends_with(".local/bin")
+        );
+    }
+
+    #[test]
+    fn test_install_options_make_relative_dirs_absolute() {
+        let config = Config {
+            share_dir: Some(PathBuf::from("rel/share")),
+            ..Config::default()
+        };
+
+        let install = InstallOptions::new(Some(PathBuf::from("rel/bin")), &config).unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(install.bin_dir, cwd.join("rel/bin"));
+        assert_eq!(install.share_dir, cwd.join("rel/share"));
+        assert_eq!(install.apps_dir, cwd.join("rel/share/grab/apps"));
+    }
+
+    fn options(dir: &Path) -> InstallOptions {
+        InstallOptions {
+            bin_dir: dir.join("bin"),
+            share_dir: dir.join("share"),
+            apps_dir: dir.join("share/grab/apps"),
+            force: false,
+        }
+    }
+
+    fn rules(app: &mut App, rules: &[&str]) {
+        app.install = rules
+            .iter()
+            .map(|rule| crate::models::InstallRule::parse(rule).unwrap())
+            .collect();
+    }
+
+    fn dests(planned: &[PlannedFile], app: &App, root: &Path) -> Vec<String> {
+        let install = options(root);
+        let mut dests: Vec<String> = planned
+            .iter()
+            .map(|file| install.link_path(app, file.target, &file.path))
+            .map(|link| slash_path(link.strip_prefix(root).unwrap()))
+            .collect();
+        dests.sort();
+        dests
+    }
+
+    #[test]
+    fn test_plan_install_maps_rules_to_target_dirs() {
+        let archive = tempdir().unwrap();
+        touch(archive.path(), "rg");
+        touch(archive.path(), "doc/rg.1");
+        touch(archive.path(), "complete/_rg");
+        touch(archive.path(), "complete/rg.bash");
+        touch(archive.path(), "complete/rg.fish");
+        touch(archive.path(), "runtime/themes/dark.toml");
+        touch(archive.path(), "runtime/queries/rust.scm");
+
+        let mut app = app(Some("rg"), None);
+        rules(
+            &mut app,
+            &[
+                "doc/*.1=man1",
+                "_rg=zsh",
+                "*.bash=bash",
+                "*.fish=fish",
+                "runtime=share",
+            ],
+        );
+
+        let out = tempdir().unwrap();
+        let planned = plan_install(archive.path(), &app).unwrap();
+        assert_eq!(
+            dests(&planned, &app, out.path()),
+            vec![
+                "bin/rg",
+                "share/bash-completion/completions/rg.bash",
+                "share/fish/vendor_completions.d/rg.fish",
+                "share/man/man1/rg.1",
+                "share/ripgrep/runtime/queries/rust.scm",
+                "share/ripgrep/runtime/themes/dark.toml",
+                "share/zsh/site-functions/_rg",
+            ]
+        );
+        assert!(planned.iter().any(|file| file.executable));
+        assert!(
+            planned
+                .iter()
+                .filter(|file| file.target != InstallTarget::Bin)
+                .all(|file| !file.executable)
+        );
+    }
+
+    #[test]
+    fn test_share_target_stays_inside_share_dir() {
+        let out = tempdir().unwrap();
+        let install = options(out.path());
+        let mut app = app(None, None);
+
+        for name in ["..", "../evil", "/etc"] {
+            app.name = name.to_string();
+            let dir = install.target_dir(InstallTarget::Share, &app);
+            assert_eq!(dir.parent(), Some(install.share_dir.as_path()));
+        }
+    }
+
+    #[test]
+    fn test_plan_install_bin_rules_replace_primary_binary() {
+        let archive = tempdir().unwrap();
+        touch(archive.path(), "kubectl-foo");
+        touch(archive.path(), "kubectl-bar");
+        touch(archive.path(), "LICENSE");
+
+        let mut app = app(None, None);
+        rules(&mut app, &["kubectl-*=bin"]);
+
+        let out = tempdir().unwrap();
+        let planned = plan_install(archive.path(), &app).unwrap();
+        assert_eq!(
+            dests(&planned, &app, out.path()),
+            vec!["bin/kubectl-bar", "bin/kubectl-foo"]
+        );
+        assert!(planned.iter().all(|file| file.executable));
+    }
+
+    #[test]
+    fn test_plan_asset_installs_bare_binary_only_without_rules() {
+        let dir = tempdir().unwrap();
+        touch(dir.path(), "rg-linux-amd64");
+        let asset = dir.path().join("rg-linux-amd64");
+        let contents = dir.path().join("contents");
+
+        let mut app = app(Some("rg"), None);
+        let planned = plan_asset(asset.clone(), &contents, &app).unwrap();
+        assert_eq!(planned, vec![primary_binary(&app, asset.clone())]);
+
+        rules(&mut app, &["doc/*.1=man1"]);
+        let result = plan_asset(asset, &contents, &app);
+        assert!(
+            matches!(result, Err(GrabError::InvalidInput(msg)) if msg.contains("install rules"))
+        );
+    }
+
+    #[test]
+    fn test_plan_install_rule_without_match_fails() {
+        let archive = tempdir().unwrap();
+        touch(archive.path(), "rg");
+
+        let mut app = app(Some("rg"), None);
+        rules(&mut app, &["doc/*.1=man1"]);
+
+        let result = plan_install(archive.path(), &app);
+        assert!(matches!(result, Err(GrabError::NotFound(msg)) if msg.contains("doc/*.1")));
+    }
+
+    #[test]
+    fn test_plan_install_rejects_conflicting_destinations() {
+        let archive = tempdir().unwrap();
+        touch(archive.path(), "rg");
+        touch(archive.path(), "a/rg.1");
+        touch(archive.path(), "b/rg.1");
+
+        let mut app = app(Some("rg"), None);
+        rules(&mut app, &["*.1=man1"]);
+
+        let result = plan_install(archive.path(), &app);
+        assert!(matches!(result, Err(GrabError::InvalidInput(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_plan_install_ignores_symlinks() {
+        let outside = tempdir().unwrap();
+        touch(outside.path(), "secret/rg.1");
+        let archive = tempdir().unwrap();
+        touch(archive.path(), "rg");
+        touch(archive.path(), "doc/rg.1");
+        std::os::unix::fs::symlink(outside.path().join("secret"), archive.path().join("extra"))
+            .unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret/rg.1"),
+            archive.path().join("rg.1"),
+        )
+        .unwrap();
+
+        let mut app = app(Some("rg"), None);
+        rules(&mut app, &["*.1=man1", "extra=share"]);
+        let result = plan_install(archive.path(), &app);
+        assert!(matches!(result, Err(GrabError::NotFound(msg)) if msg.contains("extra")));
+
+        rules(&mut app, &["*.1=man1"]);
+        let planned = plan_install(archive.path(), &app).unwrap();
+        let sources: Vec<_> = planned.iter().map(|file| file.source.clone()).collect();
+        assert_eq!(
+            sources,
+            vec![archive.path().join("rg"), archive.path().join("doc/rg.1")]
+        );
+    }
+
+    fn stage(out: &Path, app: &App, tag: &str, files: &[(&str, &[u8])]) -> InstallRecord {
+        let archive = tempdir().unwrap();
+        for (path, content) in files {
+            let path = archive.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let planned = plan_install(archive.path(), app).unwrap();
+        let url = format!("https://example.com/{tag}/rg.tar.gz");
+        stage_version(&planned, tag, &url, app, &options(out)).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_activate_switches_links_between_versions() {
+        let out = tempdir().unwrap();
+        let install = options(out.path());
+        let mut app = app(Some("rg"), None);
+        rules(&mut app, &["*.1=man1"]);
+
+        let v1 = stage(
+            out.path(),
+            &app,
+            "v1.0.0",
+            &[("rg", b"one"), ("doc/rg.1", b"man")],
+        );
+        activate(&v1, None, &install).unwrap();
+
+        let bin = out.path().join("bin/rg");
+        let man = out.path().join("share/man/man1/rg.1");
+        assert_eq!(fs::read(&bin).unwrap(), b"one");
+        assert_eq!(fs::read(&man).unwrap(), b"man");
+        assert!(fs::symlink_metadata(&bin).unwrap().file_type().is_symlink());
+
+        app.install.clear();
+        let v2 = stage(out.path(), &app, "v2.0.0", &[("rg", b"two")]);
+        activate(&v2, Some(&v1), &install).unwrap();
+
+        assert_eq!(fs::read(&bin).unwrap(), b"two");
+        assert!(fs::symlink_metadata(&man).is_err());
+        assert_eq!(
+            fs::read_link(install.app_dir(&app).join(CURRENT_LINK)).unwrap(),
+            PathBuf::from("v2.0.0")
+        );
+        assert_eq!(fs::read(v1.dir.join("bin/rg")).unwrap(), b"one");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_activate_refuses_to_replace_unmanaged_files() {
+        let out = tempdir().unwrap();
+        let app = app(Some("rg"), None);
+        let record = stage(out.path(), &app, "v1.0.0", &[("rg", b"one")]);
+
+        let bin = out.path().join("bin/rg");
+        fs::create_dir_all(bin.parent().unwrap()).unwrap();
+        fs::write(&bin, b"manual").unwrap();
+
+        let result = activate(&record, None, &options(out.path()));
+        assert!(matches!(result, Err(GrabError::FileExists(_))));
+        assert_eq!(fs::read(&bin).unwrap(), b"manual");
+
+        let forced = InstallOptions {
+            force: true,
+            ..options(out.path())
+        };
+        activate(&record, None, &forced).unwrap();
+        assert_eq!(fs::read(&bin).unwrap(), b"one");
+    }
+
+    #[test]
+    fn test_stage_version_replaces_existing_version_dir() {
+        let out = tempdir().unwrap();
+        let app = app(Some("rg"), None);
+        stage(
+            out.path(),
+            &app,
+            "v1.0.0",
+            &[("rg", b"old"), ("extra", b"x")],
+        );
+        let app_dir = options(out.path()).app_dir(&app);
+        fs::create_dir_all(app_dir.join(".grab-replaced-v1.0.0/bin")).unwrap();
+        let record = stage(out.path(), &app, "v1.0.0", &[("rg", b"new")]);
+
+        assert_eq!(record.version, "v1.0.0");
+        assert_eq!(fs::read(record.dir.join("bin/rg")).unwrap(), b"new");
+        let staged = fs::read_dir(options(out.path()).app_dir(&app)).unwrap();
+        assert_eq!(staged.count(), 1);
+    }
+
+    fn history(out: &Path, app: &mut App, versions: &[&str]) {
+        for version in versions {
+            let record = stage(out, app, version, &[("rg", version.as_bytes())]);
+            app.installs.push(record);
+        }
+    }
+
+    #[test]
+    fn test_rollback_target_picks_previous_install() {
+        let out = tempdir().unwrap();
+        let mut app = app(Some("rg"), None);
+        history(out.path(), &mut app, &["v1.0.0", "v2.0.0", "v3.0.0"]);
+
+        assert!(matches!(
+            rollback_target(&app, None),
+            Err(GrabError::NotFound(_))
+        ));
+
+        app.active_version = Some("v3.0.0".to_string());
+        assert_eq!(rollback_target(&app, None).unwrap().version, "v2.0.0");
+
+        app.active_version = Some("v2.0.0".to_string());
+        assert_eq!(rollback_target(&app, None).unwrap().version, "v1.0.0");
+
+        app.active_version = Some("v1.0.0".to_string());
+        assert!(rollback_target(&app, None).is_err());
+        assert_eq!(
+            rollback_target(&app, Some("v3.0.0")).unwrap().version,
+            "v3.0.0"
+        );
+    }
+
+    #[test]
+    fn test_rollback_target_skips_removed_versions() {
+        let out = tempdir().unwrap();
+        let mut app = app(Some("rg"), None);
+        history(out.path(), &mut app, &["v1.0.0", "v2.0.0", "v3.0.0"]);
+        app.active_version = Some("v3.0.0".to_string());
+
+        fs::remove_dir_all(&app.installs[1].dir).unwrap();
+        assert_eq!(rollback_target(&app, None).unwrap().version, "v1.0.0");
+        assert!(rollback_target(&app, Some("v2.0.0")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rollback_switches_links_back() {
+        let out = tempdir().unwrap();
+        let install = options(out.path());
+        let mut app = app(Some("rg"), None);
+        history(out.path(), &mut app, &["v1.0.0", "v2.0.0"]);
+        activate(&app.installs[1], None, &install).unwrap();
+        app.active_version = Some("v2.0.0".to_string());
+
+        let record = rollback(&app, None, &install).unwrap();
+        assert_eq!(record.version, "v1.0.0");
+        assert_eq!(fs::read(out.path().join("bin/rg")).unwrap(), b"v1.0.0");
+    }
+
+    #[test]
+    fn test_stage_version_records_receipt() {
+        let out = tempdir().unwrap();
+        let app = app(Some("rg"), None);
+        let record = stage(out.path(), &app, "v1.0.0", &[("rg", b"test")]);
+
+        assert_eq!(record.asset_url, "https://example.com/v1.0.0/rg.tar.gz");
+        assert_eq!(record.files.len(), 1);
+        assert_eq!(
+            record.files[0].sha256,
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+        assert_eq!(record.files[0].link, out.path().join("bin/rg"));
+        assert_eq!(
+            record.file_path(&record.files[0]),
+            record.dir.join("bin/rg")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_uninstall_removes_receipt_files_and_keeps_modified_ones() {
+        let out = tempdir().unwrap();
+        let install = options(out.path());
+        let mut app = app(Some("rg"), None);
+        history(out.path(), &mut app, &["v1.0.0"]);
+        rules(&mut app, &["*.1=man1"]);
+        let record = stage(
+            out.path(),
+            &app,
+            "v2.0.0",
+            &[("rg", b"two"), ("rg.1", b"man")],
+        );
+        app.installs.push(record.clone());
+        activate(&record, None, &install).unwrap();
+        app.active_version = Some("v2.0.0".to_string());
+
+        let man = record.dir.join("man1/rg.1");
+        fs::write(&man, b"edited").unwrap();
+        let unrelated = out.path().join("bin/other");
+        fs::write(&unrelated, b"keep").unwrap();
+
+        let result = uninstall(&app, false).unwrap();
+        assert_eq!(result.modified, vec![man.clone()]);
+        assert!(result.removed.contains(&out.path().join("bin/rg")));
+        assert!(fs::symlink_metadata(out.path().join("bin/rg")).is_err());
+        assert!(fs::symlink_metadata(out.path().join("share/man/man1/rg.1")).is_err());
+        assert!(!app.installs[0].dir.exists());
+        assert_eq!(fs::read(&man).unwrap(), b"edited");
+        assert_eq!(fs::read(&unrelated).unwrap(), b"keep");
+
+        let result = uninstall(&app, true).unwrap();
+        assert_eq!(result.removed, vec![man]);
+        assert!(!install.app_dir(&app).exists());
+    }
+}
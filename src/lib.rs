@@ -1,12 +1,27 @@
 pub mod app;
+pub mod asset_pattern;
+pub mod asset_score;
+pub mod cache;
+pub mod checksum;
 pub mod cli;
 pub mod client;
 pub mod compare_release_version;
+pub mod config;
+pub mod download;
 pub mod error;
+pub mod extract;
+pub mod github_feed;
 pub mod github_release;
+pub mod github_tags;
 pub mod github_version;
 pub mod handlers;
+pub mod install;
 pub mod models;
+pub mod output;
+pub mod platform;
+pub mod progress;
+pub mod ratelimit;
 pub mod remote;
+pub mod signature;
 pub mod storage;
 pub mod ui;
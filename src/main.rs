@@ -13,12 +13,16 @@ async fn run() -> Result<()> {
     let cli = Cli::parse();
     match cli.command {
         Commands::Add(args) => handlers::handle_add(args)?,
+        Commands::Cache(args) => handlers::handle_cache(args)?,
         Commands::Check(args) => handlers::handle_check(args).await?,
-        Commands::Download => handlers::handle_download().await?,
+        Commands::Download(args) => handlers::handle_download(args).await?,
+        Commands::Install(args) => handlers::handle_install(args).await?,
         Commands::Remote(args) => handlers::handle_remote_download(args).await?,
         Commands::List => handlers::handle_list()?,
         Commands::Remove(args) => handlers::handle_remove(args)?,
+        Commands::Rollback(args) => handlers::handle_rollback(args)?,
         Commands::Search(args) => handlers::handle_search(args)?,
+        Commands::Uninstall(args) => handlers::handle_uninstall(args)?,
     }
     Ok(())
 }
@@ -1,14 +1,172 @@
 use crate::error::{GrabError, Result};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceArchive {
+    Tarball,
+    Zipball,
+}
+
+impl SourceArchive {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SourceArchive::Tarball => "tar.gz",
+            SourceArchive::Zipball => "zip",
+        }
+    }
+}
+
+impl fmt::Display for SourceArchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceArchive::Tarball => write!(f, "tarball"),
+            SourceArchive::Zipball => write!(f, "zipball"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternKind {
+    #[default]
+    Substring,
+    Glob,
+    Regex,
+}
+
+impl fmt::Display for PatternKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternKind::Substring => write!(f, "substring"),
+            PatternKind::Glob => write!(f, "glob"),
+            PatternKind::Regex => write!(f, "regex"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallTarget {
+    Bin,
+    Man1,
+    Bash,
+    Zsh,
+    Fish,
+    Share,
+}
+
+impl fmt::Display for InstallTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstallTarget::Bin => write!(f, "bin"),
+            InstallTarget::Man1 => write!(f, "man1"),
+            InstallTarget::Bash => write!(f, "bash"),
+            InstallTarget::Zsh => write!(f, "zsh"),
+            InstallTarget::Fish => write!(f, "fish"),
+            InstallTarget::Share => write!(f, "share"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallRule {
+    pub source: String,
+    pub target: InstallTarget,
+}
+
+impl InstallRule {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || {
+            GrabError::InvalidInput(format!(
+                "Invalid install rule '{value}', expected GLOB=TARGET with TARGET one of bin, man1, bash, zsh, fish or share"
+            ))
+        };
+
+        let (source, target) = value.rsplit_once('=').ok_or_else(invalid)?;
+        let source = source.trim();
+        if source.is_empty() {
+            return Err(invalid());
+        }
+        let target = InstallTarget::from_str(target.trim(), true).map_err(|_| invalid())?;
+
+        Ok(Self {
+            source: source.to_string(),
+            target,
+        })
+    }
+}
+
+impl fmt::Display for InstallRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.source, self.target)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledFile {
+    pub link: PathBuf,
+    pub target: InstallTarget,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallRecord {
+    pub version: String,
+    pub dir: PathBuf,
+    #[serde(default)]
+    pub asset_url: String,
+    pub installed_at: u64,
+    pub files: Vec<InstalledFile>,
+}
+
+impl InstallRecord {
+    pub fn file_path(&self, file: &InstalledFile) -> PathBuf {
+        self.dir.join(file.target.to_string()).join(&file.path)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct App {
     pub name: String,
     pub owner: String,
     pub repo: String,
-    pub asset_pattern: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_pattern: Option<String>,
     pub version_flag: String,
+    #[serde(default)]
+    pub pattern_kind: PatternKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_default_excludes: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceArchive>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_checksum: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minisign_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bin_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub install: Vec<InstallRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub installs: Vec<InstallRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_version: Option<String>,
 }
 
 impl App {
@@ -22,7 +180,7 @@ impl App {
         let name = Self::validate_non_empty(name.into(), "Name")?;
         let owner = Self::validate_non_empty(owner.into(), "Owner")?;
         let repo = Self::validate_non_empty(repo.into(), "Repo")?;
-        let asset_pattern = Self::validate_non_empty(asset_pattern.into(), "Asset pattern")?;
+        let asset_pattern = Self::optional_non_empty(asset_pattern.into());
         let version_flag = Self::validate_non_empty(version_flag.into(), "Version flag")?;
 
         Ok(Self {
@@ -31,9 +189,37 @@ impl App {
             repo,
             asset_pattern,
             version_flag,
+            pattern_kind: PatternKind::default(),
+            exclude: Vec::new(),
+            no_default_excludes: false,
+            source: None,
+            dest: None,
+            require_checksum: false,
+            minisign_key: None,
+            ssh_key: None,
+            ssh_namespace: None,
+            bin: None,
+            bin_path: None,
+            install: Vec::new(),
+            installs: Vec::new(),
+            active_version: None,
         })
     }
 
+    pub fn bin_name(&self) -> &str {
+        self.bin.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn active_install(&self) -> Option<&InstallRecord> {
+        let active = self.active_version.as_deref()?;
+        self.installs.iter().find(|record| record.version == active)
+    }
+
+    fn optional_non_empty(field: String) -> Option<String> {
+        let trimmed = field.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    }
+
     fn validate_non_empty(field: String, field_name: &str) -> Result<String> {
         let trimmed = field.trim();
         if trimmed.is_empty() {
@@ -51,8 +237,59 @@ impl fmt::Display for App {
         write!(
             f,
             "Name: {}, Owner: {}, Repo: {}, Asset Pattern: {}, Version flag: {}",
-            self.name, self.owner, self.repo, self.asset_pattern, self.version_flag
-        )
+            self.name,
+            self.owner,
+            self.repo,
+            self.asset_pattern.as_deref().unwrap_or("auto"),
+            self.version_flag
+        )?;
+
+        if self.pattern_kind != PatternKind::Substring {
+            write!(f, ", Pattern kind: {}", self.pattern_kind)?;
+        }
+
+        if !self.exclude.is_empty() {
+            write!(f, ", Exclude: {}", self.exclude.join(" "))?;
+        }
+
+        if let Some(source) = self.source {
+            write!(f, ", Source: {source}")?;
+        }
+
+        if let Some(dest) = &self.dest {
+            write!(f, ", Dest: {}", dest.display())?;
+        }
+
+        if self.require_checksum {
+            write!(f, ", Require checksum")?;
+        }
+
+        if let Some(key) = &self.minisign_key {
+            write!(f, ", Minisign key: {}", key.trim())?;
+        }
+
+        if let Some(key) = &self.ssh_key {
+            write!(f, ", SSH key: {}", key.trim())?;
+        }
+
+        if let Some(bin) = &self.bin {
+            write!(f, ", Bin: {bin}")?;
+        }
+
+        if let Some(bin_path) = &self.bin_path {
+            write!(f, ", Bin path: {bin_path}")?;
+        }
+
+        if !self.install.is_empty() {
+            let rules: Vec<String> = self.install.iter().map(ToString::to_string).collect();
+            write!(f, ", Install: {}", rules.join(" "))?;
+        }
+
+        if let Some(version) = &self.active_version {
+            write!(f, ", Installed: {version}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -77,7 +314,7 @@ mod tests {
         assert_eq!(app.name, "ripgrep");
         assert_eq!(app.owner, "BurntSushi");
         assert_eq!(app.repo, "ripgrep");
-        assert_eq!(app.asset_pattern, "ripgrep-linux");
+        assert_eq!(app.asset_pattern.as_deref(), Some("ripgrep-linux"));
         assert_eq!(app.version_flag, "--version");
     }
 
@@ -118,19 +355,16 @@ mod tests {
     }
 
     #[test]
-    fn test_whitespace_only_asset_pattern_is_invalid() {
-        let result = App::new("name", "owner", "repo", "  ", "--version");
-        assert!(
-            matches!(result, Err(GrabError::InvalidInput(msg)) if msg.contains("Asset pattern"))
-        );
+    fn test_whitespace_only_asset_pattern_means_auto_detect() {
+        let app = App::new("name", "owner", "repo", "  ", "--version").unwrap();
+        assert!(app.asset_pattern.is_none());
     }
 
     #[test]
-    fn test_empty_asset_pattern_is_invalid() {
-        let result = App::new("name", "owner", "repo", "", "--version");
-        assert!(
-            matches!(result, Err(GrabError::InvalidInput(msg)) if msg.contains("Asset pattern"))
-        );
+    fn test_empty_asset_pattern_means_auto_detect() {
+        let app = App::new("name", "owner", "repo", "", "--version").unwrap();
+        assert!(app.asset_pattern.is_none());
+        assert!(app.to_string().contains("Asset Pattern: auto"));
     }
 
     #[test]
@@ -162,7 +396,29 @@ mod tests {
         assert_eq!(app.name, "ripgrep");
         assert_eq!(app.owner, "BurntSushi");
         assert_eq!(app.repo, "ripgrep");
-        assert_eq!(app.asset_pattern, "linux");
+        assert_eq!(app.asset_pattern.as_deref(), Some("linux"));
         assert_eq!(app.version_flag, "--version");
     }
+
+    #[test]
+    fn test_parse_install_rule() {
+        let rule = InstallRule::parse("complete/_rg=zsh").unwrap();
+        assert_eq!(rule.source, "complete/_rg");
+        assert_eq!(rule.target, InstallTarget::Zsh);
+        assert_eq!(rule.to_string(), "complete/_rg=zsh");
+
+        let rule = InstallRule::parse("doc/*.1 = MAN1").unwrap();
+        assert_eq!(rule.source, "doc/*.1");
+        assert_eq!(rule.target, InstallTarget::Man1);
+    }
+
+    #[test]
+    fn test_parse_install_rule_rejects_bad_input() {
+        for value in ["doc/rg.1", "=bin", "rg=sbin"] {
+            assert!(matches!(
+                InstallRule::parse(value),
+                Err(GrabError::InvalidInput(_))
+            ));
+        }
+    }
 }
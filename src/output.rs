@@ -0,0 +1,308 @@
+use crate::asset_pattern::version_from_tag;
+use crate::error::{GrabError, Result};
+use crate::models::App;
+use directories::UserDirs;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::path::{Path, PathBuf};
+
+static TEMPLATE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{([^{}]*)\}").expect("Invalid regex check the output template pattern syntax.")
+});
+
+pub const DEFAULT_TEMPLATE: &str = "{asset}";
+
+const COMPOUND_EXTENSIONS: [&str; 5] = [".tar.gz", ".tar.xz", ".tar.zst", ".tar.bz2", ".tar.lz"];
+
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    pub dir: Option<PathBuf>,
+    pub default_dir: Option<PathBuf>,
+    pub template: Option<String>,
+}
+
+impl OutputOptions {
+    pub fn directory(&self, app: &App) -> PathBuf {
+        self.dir
+            .as_ref()
+            .or(app.dest.as_ref())
+            .or(self.default_dir.as_ref())
+            .map(|dir| expand_home(dir))
+            .unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let template = self.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+        render_file_name(template, "app", "v0.0.0", "app.tar.gz").map(|_| ())
+    }
+
+    pub fn path_for(&self, app: &App, tag: &str, asset: &str) -> Result<PathBuf> {
+        let template = self.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+        let asset = safe_file_name(asset)?;
+        let file_name = render_file_name(template, &app.name, tag, asset)?;
+        let file_name = safe_file_name(&file_name)?;
+        Ok(self.directory(app).join(file_name))
+    }
+}
+
+pub fn render_file_name(template: &str, name: &str, tag: &str, asset: &str) -> Result<String> {
+    let (stem, ext) = split_extension(asset);
+    let mut unknown = None;
+
+    let rendered = TEMPLATE_RE.replace_all(template, |caps: &Captures| match &caps[1] {
+        "name" => name.to_string(),
+        "version" => version_from_tag(tag).to_string(),
+        "tag" => tag.to_string(),
+        "asset" => asset.to_string(),
+        "stem" => stem.to_string(),
+        "ext" => ext.to_string(),
+        other => {
+            unknown.get_or_insert_with(|| other.to_string());
+            String::new()
+        }
+    });
+
+    if let Some(placeholder) = unknown {
+        return Err(GrabError::InvalidInput(format!(
+            "Unknown placeholder {{{placeholder}}} in output template '{template}', expected {{name}}, {{version}}, {{tag}}, {{asset}}, {{stem}} or {{ext}}"
+        )));
+    }
+
+    let literal = TEMPLATE_RE.replace_all(template, "");
+    if rendered.is_empty() || literal.contains(['/', '\\']) {
+        return Err(GrabError::InvalidInput(format!(
+            "Output template '{template}' must produce a plain file name, got '{rendered}'"
+        )));
+    }
+
+    Ok(rendered.into_owned())
+}
+
+pub fn safe_file_name(name: &str) -> Result<&str> {
+    let unsafe_name = |reason: &str| {
+        Err(GrabError::UnsafeFileName {
+            name: name.escape_debug().to_string(),
+            reason: reason.to_string(),
+        })
+    };
+
+    if name.is_empty() {
+        return unsafe_name("it is empty");
+    }
+    if name == "." || name == ".." {
+        return unsafe_name("it refers to a directory");
+    }
+    if name.contains(['/', '\\']) || Path::new(name).is_absolute() {
+        return unsafe_name("it contains a path separator");
+    }
+    if name.chars().any(char::is_control) {
+        return unsafe_name("it contains control characters");
+    }
+    if name.len() > 255 {
+        return unsafe_name("it is longer than 255 bytes");
+    }
+
+    Ok(name)
+}
+
+pub fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c == '/' || c == '\\' || c.is_control() {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    match sanitized.trim_start_matches('.') {
+        "" => "download".to_string(),
+        _ => sanitized,
+    }
+}
+
+pub fn split_extension(name: &str) -> (&str, &str) {
+    let lower = name.to_lowercase();
+    if let Some(ext) = COMPOUND_EXTENSIONS
+        .iter()
+        .find(|ext| lower.ends_with(*ext) && lower.len() > ext.len())
+    {
+        return name.split_at(name.len() - ext.len());
+    }
+
+    match name.rfind('.') {
+        Some(index) if index > 0 && name[index + 1..].chars().any(|c| c.is_ascii_alphabetic()) => {
+            name.split_at(index)
+        }
+        _ => (name, ""),
+    }
+}
+
+pub fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => match UserDirs::new() {
+            Some(user_dirs) => user_dirs.home_dir().join(rest),
+            None => path.to_path_buf(),
+        },
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(dest: Option<&str>) -> App {
+        App {
+            name: "rg".to_string(),
+            dest: dest.map(PathBuf::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_extension() {
+        assert_eq!(
+            split_extension("tool-1.2.3-linux.tar.gz"),
+            ("tool-1.2.3-linux", ".tar.gz")
+        );
+        assert_eq!(split_extension("tool.zip"), ("tool", ".zip"));
+        assert_eq!(split_extension("tool-1.2.3"), ("tool-1.2.3", ""));
+        assert_eq!(split_extension("tool"), ("tool", ""));
+        assert_eq!(split_extension(".hidden"), (".hidden", ""));
+    }
+
+    #[test]
+    fn test_render_file_name_placeholders() {
+        let rendered = render_file_name(
+            "{name}-{version}{ext}",
+            "rg",
+            "v14.1.1",
+            "ripgrep-14.1.1-x86_64-unknown-linux-musl.tar.gz",
+        )
+        .unwrap();
+        assert_eq!(rendered, "rg-14.1.1.tar.gz");
+
+        let rendered = render_file_name("{tag}_{stem}", "rg", "v1", "tool.zip").unwrap();
+        assert_eq!(rendered, "v1_tool");
+    }
+
+    #[test]
+    fn test_render_file_name_default_keeps_asset_name() {
+        let rendered = render_file_name(DEFAULT_TEMPLATE, "rg", "v1", "tool.zip").unwrap();
+        assert_eq!(rendered, "tool.zip");
+    }
+
+    #[test]
+    fn test_render_file_name_rejects_unknown_placeholder() {
+        let result = render_file_name("{name}-{platform}", "rg", "v1", "tool.zip");
+        assert!(matches!(result, Err(GrabError::InvalidInput(msg)) if msg.contains("{platform}")));
+    }
+
+    #[test]
+    fn test_render_file_name_rejects_paths() {
+        assert!(render_file_name("bin/{asset}", "rg", "v1", "tool.zip").is_err());
+        assert!(render_file_name("{nothing}", "rg", "v1", "tool.zip").is_err());
+    }
+
+    #[test]
+    fn test_safe_file_name_accepts_plain_names() {
+        assert_eq!(
+            safe_file_name("tool-1.0-linux.tar.gz").unwrap(),
+            "tool-1.0-linux.tar.gz"
+        );
+        assert!(safe_file_name(".hidden-but-fine").is_ok());
+    }
+
+    #[test]
+    fn test_safe_file_name_rejects_traversal() {
+        for name in [
+            "",
+            ".",
+            "..",
+            "../../.bashrc",
+            "/etc/passwd",
+            "dir\\file",
+            "a/b",
+        ] {
+            assert!(
+                matches!(safe_file_name(name), Err(GrabError::UnsafeFileName { .. })),
+                "{name} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_safe_file_name_rejects_control_characters() {
+        assert!(safe_file_name("tool\n.tar.gz").is_err());
+        assert!(safe_file_name("tool\u{1b}[31m").is_err());
+        assert!(safe_file_name("tool\0").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("release/1.0"), "release-1.0");
+        assert_eq!(sanitize_file_name(".."), "download");
+        assert_eq!(sanitize_file_name("v1.0"), "v1.0");
+    }
+
+    #[test]
+    fn test_path_for_rejects_unsafe_asset_and_tag() {
+        let options = OutputOptions::default();
+        assert!(matches!(
+            options.path_for(&app(None), "v1", "../../.bashrc"),
+            Err(GrabError::UnsafeFileName { .. })
+        ));
+
+        let options = OutputOptions {
+            template: Some("{name}-{tag}{ext}".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            options.path_for(&app(None), "../../x", "tool.zip"),
+            Err(GrabError::UnsafeFileName { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_checks_template() {
+        let mut options = OutputOptions::default();
+        assert!(options.validate().is_ok());
+
+        options.template = Some("{name}-{arch}".to_string());
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn test_directory_precedence() {
+        let options = OutputOptions {
+            dir: None,
+            default_dir: Some(PathBuf::from("/config")),
+            template: None,
+        };
+        assert_eq!(options.directory(&app(None)), PathBuf::from("/config"));
+        assert_eq!(options.directory(&app(Some("/app"))), PathBuf::from("/app"));
+
+        let options = OutputOptions {
+            dir: Some(PathBuf::from("/cli")),
+            ..options
+        };
+        assert_eq!(options.directory(&app(Some("/app"))), PathBuf::from("/cli"));
+    }
+
+    #[test]
+    fn test_path_for_defaults_to_current_directory() {
+        let options = OutputOptions::default();
+        let path = options.path_for(&app(None), "v1", "tool.zip").unwrap();
+        assert_eq!(path, PathBuf::from("tool.zip"));
+    }
+
+    #[test]
+    fn test_expand_home_leaves_other_paths() {
+        assert_eq!(expand_home(Path::new("/tmp/x")), PathBuf::from("/tmp/x"));
+        assert_eq!(expand_home(Path::new("dl")), PathBuf::from("dl"));
+        assert!(!expand_home(Path::new("~/dl")).starts_with("~"));
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    Linux,
+    MacOs,
+    Windows,
+    FreeBsd,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+    X86,
+    Arm,
+    Riscv64,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Libc {
+    Gnu,
+    Musl,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Platform {
+    pub os: Os,
+    pub arch: Arch,
+    pub libc: Libc,
+}
+
+const ALL_OS: [Os; 4] = [Os::Linux, Os::MacOs, Os::Windows, Os::FreeBsd];
+const ALL_ARCH: [Arch; 5] = [
+    Arch::X86_64,
+    Arch::Aarch64,
+    Arch::X86,
+    Arch::Arm,
+    Arch::Riscv64,
+];
+
+impl Os {
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Os::Linux => &["linux"],
+            Os::MacOs => &["darwin", "macos", "apple", "osx", "mac"],
+            Os::Windows => &["windows", "win64", "win32", "win", "msvc"],
+            Os::FreeBsd => &["freebsd"],
+            Os::Other => &[],
+        }
+    }
+
+    pub fn all() -> &'static [Os] {
+        &ALL_OS
+    }
+
+    pub fn go_name(&self) -> String {
+        match self {
+            Os::MacOs => "darwin".to_string(),
+            os => os.to_string(),
+        }
+    }
+}
+
+impl Arch {
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Arch::X86_64 => &["x86_64", "x86-64", "amd64", "x64", "64bit", "64-bit"],
+            Arch::Aarch64 => &["aarch64", "arm64", "armv8"],
+            Arch::X86 => &["i686", "i586", "i386", "386", "x86", "32bit", "32-bit"],
+            Arch::Arm => &["armv7", "armv7l", "armhf", "armv6", "arm"],
+            Arch::Riscv64 => &["riscv64", "riscv64gc"],
+            Arch::Other => &[],
+        }
+    }
+
+    pub fn all() -> &'static [Arch] {
+        &ALL_ARCH
+    }
+
+    pub fn go_name(&self) -> String {
+        match self {
+            Arch::X86_64 => "amd64".to_string(),
+            Arch::Aarch64 => "arm64".to_string(),
+            Arch::X86 => "386".to_string(),
+            Arch::Arm => "arm".to_string(),
+            Arch::Riscv64 => "riscv64".to_string(),
+            Arch::Other => self.to_string(),
+        }
+    }
+}
+
+impl Libc {
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Libc::Gnu => &["gnu", "glibc", "gnueabihf"],
+            Libc::Musl => &["musl", "musleabihf", "static"],
+            Libc::None => &[],
+        }
+    }
+}
+
+impl Platform {
+    pub fn detect() -> Self {
+        let os = match std::env::consts::OS {
+            "linux" => Os::Linux,
+            "macos" => Os::MacOs,
+            "windows" => Os::Windows,
+            "freebsd" => Os::FreeBsd,
+            _ => Os::Other,
+        };
+
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => Arch::X86_64,
+            "aarch64" => Arch::Aarch64,
+            "x86" => Arch::X86,
+            "arm" => Arch::Arm,
+            "riscv64" => Arch::Riscv64,
+            _ => Arch::Other,
+        };
+
+        let libc = if os != Os::Linux {
+            Libc::None
+        } else if cfg!(target_env = "musl") || has_musl_loader() {
+            Libc::Musl
+        } else {
+            Libc::Gnu
+        };
+
+        Self { os, arch, libc }
+    }
+
+    pub fn target_triple(&self) -> String {
+        let env = match (self.libc, self.arch) {
+            (Libc::Gnu, Arch::Arm) => "-gnueabihf",
+            (Libc::Musl, Arch::Arm) => "-musleabihf",
+            (Libc::Gnu, _) => "-gnu",
+            (Libc::Musl, _) => "-musl",
+            (Libc::None, _) => "",
+        };
+
+        match self.os {
+            Os::Linux => format!("{}-unknown-linux{}", self.arch, env),
+            Os::MacOs => format!("{}-apple-darwin", self.arch),
+            Os::Windows => format!("{}-pc-windows-msvc", self.arch),
+            Os::FreeBsd => format!("{}-unknown-freebsd", self.arch),
+            Os::Other => format!("{}-unknown-{}", self.arch, self.os),
+        }
+    }
+}
+
+fn has_musl_loader() -> bool {
+    std::fs::read_dir("/lib")
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.file_name().to_string_lossy().starts_with("ld-musl-"))
+        })
+        .unwrap_or(false)
+}
+
+pub fn contains_token(name: &str, token: &str) -> bool {
+    let is_boundary = |c: Option<char>| c.is_none_or(|c| !c.is_ascii_alphanumeric());
+
+    name.match_indices(token).any(|(start, _)| {
+        let before = name[..start].chars().next_back();
+        let after = name[start + token.len()..].chars().next();
+        is_boundary(before) && is_boundary(after)
+    })
+}
+
+impl fmt::Display for Os {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Os::Linux => write!(f, "linux"),
+            Os::MacOs => write!(f, "darwin"),
+            Os::Windows => write!(f, "windows"),
+            Os::FreeBsd => write!(f, "freebsd"),
+            Os::Other => write!(f, "{}", std::env::consts::OS),
+        }
+    }
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arch::X86_64 => write!(f, "x86_64"),
+            Arch::Aarch64 => write!(f, "aarch64"),
+            Arch::X86 => write!(f, "i686"),
+            Arch::Arm => write!(f, "armv7"),
+            Arch::Riscv64 => write!(f, "riscv64gc"),
+            Arch::Other => write!(f, "{}", std::env::consts::ARCH),
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.libc {
+            Libc::Gnu => write!(f, "{}-{} (gnu)", self.os, self.arch),
+            Libc::Musl => write!(f, "{}-{} (musl)", self.os, self.arch),
+            Libc::None => write!(f, "{}-{}", self.os, self.arch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_token_requires_boundaries() {
+        assert!(contains_token("tool-linux-amd64.tar.gz", "linux"));
+        assert!(contains_token("tool_x86_64_linux", "x86_64"));
+        assert!(!contains_token("tool-darwin.tar.gz", "win"));
+        assert!(!contains_token("tool-arm64.tar.gz", "arm"));
+    }
+
+    #[test]
+    fn test_contains_token_at_edges() {
+        assert!(contains_token("linux", "linux"));
+        assert!(contains_token("amd64.zip", "amd64"));
+        assert!(!contains_token("", "linux"));
+    }
+
+    #[test]
+    fn test_target_triple() {
+        let linux = Platform {
+            os: Os::Linux,
+            arch: Arch::X86_64,
+            libc: Libc::Musl,
+        };
+        let macos = Platform {
+            os: Os::MacOs,
+            arch: Arch::Aarch64,
+            libc: Libc::None,
+        };
+        let arm = Platform {
+            os: Os::Linux,
+            arch: Arch::Arm,
+            libc: Libc::Gnu,
+        };
+        assert_eq!(linux.target_triple(), "x86_64-unknown-linux-musl");
+        assert_eq!(macos.target_triple(), "aarch64-apple-darwin");
+        assert_eq!(arm.target_triple(), "armv7-unknown-linux-gnueabihf");
+        assert_eq!(
+            Platform {
+                libc: Libc::Musl,
+                ..arm
+            }
+            .target_triple(),
+            "armv7-unknown-linux-musleabihf"
+        );
+    }
+
+    #[test]
+    fn test_go_names() {
+        assert_eq!(Arch::X86_64.go_name(), "amd64");
+        assert_eq!(Arch::Aarch64.go_name(), "arm64");
+        assert_eq!(Arch::X86.go_name(), "386");
+        assert_eq!(Os::MacOs.go_name(), "darwin");
+        assert_eq!(Os::Linux.go_name(), "linux");
+    }
+
+    #[test]
+    fn test_detect_matches_build_target() {
+        let platform = Platform::detect();
+        if cfg!(target_os = "linux") {
+            assert_eq!(platform.os, Os::Linux);
+            assert_ne!(platform.libc, Libc::None);
+        }
+        if cfg!(target_arch = "x86_64") {
+            assert_eq!(platform.arch, Arch::X86_64);
+        }
+    }
+}
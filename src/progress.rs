@@ -0,0 +1,197 @@
+use crate::ui;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::{self, IsTerminal};
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant};
+
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+const FILE_TEMPLATE: &str =
+    "{msg:30!} [{bar:30.cyan/blue}] {bytes}/{total_bytes} {bytes_per_sec} ETA {eta}";
+const SPINNER_TEMPLATE: &str = "{spinner} {msg:30!} {bytes} {bytes_per_sec}";
+const TOTAL_TEMPLATE: &str =
+    "{msg:30!} [{bar:30.green/white}] {bytes}/{total_bytes} {bytes_per_sec} ETA {eta}";
+
+#[derive(Debug, Clone)]
+pub struct Progress {
+    multi: Option<MultiProgress>,
+    overall: ProgressBar,
+    overall_added: Arc<Once>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    name: String,
+    bar: ProgressBar,
+    overall: ProgressBar,
+    last_log: Option<Arc<Mutex<Instant>>>,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        if io::stdout().is_terminal() {
+            let overall = ProgressBar::new(0)
+                .with_style(style(TOTAL_TEMPLATE))
+                .with_message("Total");
+            Self {
+                multi: Some(MultiProgress::new()),
+                overall,
+                overall_added: Arc::new(Once::new()),
+            }
+        } else {
+            Self::plain()
+        }
+    }
+
+    pub fn plain() -> Self {
+        Self {
+            multi: None,
+            overall: ProgressBar::with_draw_target(Some(0), ProgressDrawTarget::hidden()),
+            overall_added: Arc::new(Once::new()),
+        }
+    }
+
+    pub fn start(&self, name: &str, size: Option<u64>) -> Transfer {
+        self.overall.inc_length(size.unwrap_or(0));
+
+        let bar = match &self.multi {
+            Some(multi) => {
+                self.overall_added.call_once(|| {
+                    multi.add(self.overall.clone());
+                });
+                let bar = match size {
+                    Some(size) => ProgressBar::new(size).with_style(style(FILE_TEMPLATE)),
+                    None => ProgressBar::new_spinner().with_style(style(SPINNER_TEMPLATE)),
+                };
+                multi.insert_before(&self.overall, bar.with_message(name.to_string()))
+            }
+            None => ProgressBar::with_draw_target(size, ProgressDrawTarget::hidden()),
+        };
+
+        Transfer {
+            name: name.to_string(),
+            bar,
+            overall: self.overall.clone(),
+            last_log: self
+                .multi
+                .is_none()
+                .then(|| Arc::new(Mutex::new(Instant::now()))),
+        }
+    }
+
+    pub fn println(&self, message: impl AsRef<str>) {
+        match &self.multi {
+            Some(multi) => {
+                let _ = multi.println(message.as_ref());
+            }
+            None => println!("{}", message.as_ref()),
+        }
+    }
+
+    pub fn suspend<F: FnOnce() -> R, R>(&self, f: F) -> R {
+        match &self.multi {
+            Some(multi) => multi.suspend(f),
+            None => f(),
+        }
+    }
+
+    pub fn finish(&self) {
+        self.overall.finish_and_clear();
+    }
+}
+
+impl Transfer {
+    pub fn resume_at(&self, offset: u64) {
+        self.bar.set_position(offset);
+        self.overall.inc(offset);
+    }
+
+    pub fn inc(&self, bytes: u64) {
+        self.bar.inc(bytes);
+        self.overall.inc(bytes);
+
+        if let Some(last_log) = &self.last_log {
+            let mut last_log = last_log.lock().unwrap_or_else(|e| e.into_inner());
+            if last_log.elapsed() >= LOG_INTERVAL {
+                *last_log = Instant::now();
+                println!("{}", self.status_line());
+            }
+        }
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+
+    pub fn abandon(&self) {
+        self.bar
+            .abandon_with_message(format!("{} (failed)", self.name));
+    }
+
+    fn status_line(&self) -> String {
+        let position = self.bar.position();
+        let speed = format!("{}/s", ui::format_size(self.bar.per_sec() as u64));
+
+        match self.bar.length().filter(|length| *length > 0) {
+            Some(length) => format!(
+                "{}: {} / {} ({}%), {}, ETA {}s",
+                self.name,
+                ui::format_size(position),
+                ui::format_size(length),
+                position * 100 / length,
+                speed,
+                self.bar.eta().as_secs()
+            ),
+            None => format!("{}: {}, {}", self.name, ui::format_size(position), speed),
+        }
+    }
+}
+
+fn style(template: &str) -> ProgressStyle {
+    ProgressStyle::with_template(template)
+        .expect("Invalid progress template check the template syntax.")
+        .progress_chars("=> ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_transfer_tracks_overall_progress() {
+        let progress = Progress::plain();
+        let first = progress.start("a.tar.gz", Some(100));
+        let second = progress.start("b.tar.gz", Some(50));
+
+        first.resume_at(40);
+        first.inc(10);
+        second.inc(50);
+
+        assert_eq!(progress.overall.length(), Some(150));
+        assert_eq!(progress.overall.position(), 100);
+    }
+
+    #[test]
+    fn test_status_line_with_known_size() {
+        let progress = Progress::plain();
+        let transfer = progress.start("tool.tar.gz", Some(2048));
+        transfer.inc(1024);
+
+        let line = transfer.status_line();
+        assert!(line.starts_with("tool.tar.gz: 1.0 KiB / 2.0 KiB (50%)"));
+    }
+
+    #[test]
+    fn test_status_line_with_unknown_size() {
+        let progress = Progress::plain();
+        let transfer = progress.start("source.zip", None);
+        transfer.inc(10);
+
+        assert!(transfer.status_line().starts_with("source.zip: 10 B, "));
+    }
+}
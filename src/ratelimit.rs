@@ -0,0 +1,138 @@
+use crate::error::{GrabError, Result};
+use crate::ui;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    bucket: Option<Arc<Mutex<Bucket>>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(bytes_per_second: Option<u64>) -> Self {
+        let bucket = bytes_per_second
+            .filter(|rate| *rate > 0)
+            .map(|rate| Arc::new(Mutex::new(Bucket::new(rate as f64, Instant::now()))));
+        Self { bucket }
+    }
+
+    pub async fn consume(&self, bytes: usize) {
+        let Some(bucket) = &self.bucket else {
+            return;
+        };
+
+        // The lock is held while sleeping so concurrent streams queue up
+        // behind each other instead of all bursting once the wait is over.
+        let mut bucket = bucket.lock().await;
+        let wait = bucket.take(bytes as f64, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Bucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: rate,
+            updated: now,
+        }
+    }
+
+    fn take(&mut self, bytes: f64, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.updated = now;
+        self.tokens -= bytes;
+
+        if self.tokens < 0.0 {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+pub fn parse_rate(value: &str) -> Result<u64> {
+    let invalid = || {
+        GrabError::InvalidInput(format!(
+            "Invalid rate '{value}', expected a number with an optional K, M or G suffix"
+        ))
+    };
+
+    let trimmed = value.trim();
+    let trimmed = trimmed.strip_suffix("/s").unwrap_or(trimmed);
+    ui::parse_size(trimmed).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate_suffixes() {
+        assert_eq!(parse_rate("512").unwrap(), 512);
+        assert_eq!(parse_rate("100k").unwrap(), 100 * 1024);
+        assert_eq!(parse_rate("5M").unwrap(), 5 * 1024 * 1024);
+        assert_eq!(parse_rate("1G").unwrap(), 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_rate_accepts_units_and_fractions() {
+        assert_eq!(parse_rate("1.5MB").unwrap(), 1536 * 1024);
+        assert_eq!(parse_rate("2MiB/s").unwrap(), 2 * 1024 * 1024);
+        assert_eq!(parse_rate(" 10K ").unwrap(), 10 * 1024);
+    }
+
+    #[test]
+    fn test_parse_rate_rejects_garbage() {
+        assert!(parse_rate("").is_err());
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("0").is_err());
+        assert!(parse_rate("-5M").is_err());
+        assert!(parse_rate("5T").is_err());
+    }
+
+    #[test]
+    fn test_bucket_allows_initial_burst() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(1000.0, now);
+        assert_eq!(bucket.take(1000.0, now), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_bucket_waits_for_deficit() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(1000.0, now);
+        bucket.take(1000.0, now);
+        assert_eq!(bucket.take(500.0, now), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_bucket_refills_up_to_capacity() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(1000.0, now);
+        bucket.take(1000.0, now);
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(bucket.take(1000.0, later), Duration::ZERO);
+        assert_eq!(bucket.take(1000.0, later), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_limiter_never_waits() {
+        let limiter = RateLimiter::new(None);
+        let start = Instant::now();
+        limiter.consume(usize::MAX).await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::error::{GrabError, Result};
+use minisign_verify::{PublicKey, Signature as MinisignSignature};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::fs::File as TokioFile;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+pub const MINISIGN_SUFFIX: &str = ".minisig";
+pub const SSH_SIGNATURE_SUFFIXES: [&str; 2] = [".sig", ".sshsig"];
+pub const DEFAULT_SSH_NAMESPACE: &str = "file";
+const SSH_PRINCIPAL: &str = "grab";
+
+#[derive(Debug, Clone)]
+pub enum Signature {
+    Minisign {
+        public_key: String,
+        signature: String,
+    },
+    Ssh {
+        public_key: String,
+        namespace: String,
+        signature: String,
+    },
+}
+
+impl Signature {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Signature::Minisign { .. } => "minisign",
+            Signature::Ssh { .. } => "SSH",
+        }
+    }
+
+    pub async fn verify(&self, path: &Path, name: &str) -> Result<()> {
+        let result = match self {
+            Signature::Minisign {
+                public_key,
+                signature,
+            } => verify_minisign(public_key, signature, path).await,
+            Signature::Ssh {
+                public_key,
+                namespace,
+                signature,
+            } => verify_ssh(public_key, namespace, signature, path).await,
+        };
+
+        result.map_err(|e| match e {
+            GrabError::InvalidInput(reason) => GrabError::SignatureInvalid {
+                name: name.to_string(),
+                kind: self.kind().to_string(),
+                reason,
+            },
+            other => other,
+        })
+    }
+}
+
+pub fn validate_minisign_key(key: &str) -> Result<()> {
+    minisign_public_key(key).map(|_| ())
+}
+
+pub fn validate_ssh_key(key: &str) -> Result<()> {
+    let mut fields = key.split_whitespace();
+    let key_type = fields.next().unwrap_or_default();
+    let valid_type = ["ssh-", "ecdsa-", "sk-"]
+        .iter()
+        .any(|prefix| key_type.starts_with(prefix));
+
+    if valid_type && fields.next().is_some() && !key.contains('\n') {
+        Ok(())
+    } else {
+        Err(GrabError::InvalidInput(format!(
+            "Invalid SSH public key '{key}', expected a single line such as 'ssh-ed25519 AAAA...'"
+        )))
+    }
+}
+
+fn minisign_public_key(key: &str) -> Result<PublicKey> {
+    let key = key.trim();
+    let parsed = if key.contains('\n') {
+        PublicKey::decode(key)
+    } else {
+        PublicKey::from_base64(key)
+    };
+
+    parsed.map_err(|e| GrabError::InvalidInput(format!("Invalid minisign public key: {e}")))
+}
+
+async fn verify_minisign(public_key: &str, signature: &str, path: &Path) -> Result<()> {
+    let public_key = minisign_public_key(public_key)?;
+    let signature = MinisignSignature::decode(signature)
+        .map_err(|e| GrabError::InvalidInput(format!("malformed signature: {e}")))?;
+    let invalid = |e: minisign_verify::Error| GrabError::InvalidInput(e.to_string());
+
+    let mut verifier = match public_key.verify_stream(&signature) {
+        Ok(verifier) => verifier,
+        Err(minisign_verify::Error::UnsupportedLegacyMode) => {
+            let content = tokio::fs::read(path).await?;
+            return public_key
+                .verify(&content, &signature, true)
+                .map_err(invalid);
+        }
+        Err(e) => return Err(invalid(e)),
+    };
+
+    let mut file = TokioFile::open(path).await?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return verifier.finalize().map_err(invalid);
+        }
+        verifier.update(&buffer[..read]);
+    }
+}
+
+async fn verify_ssh(public_key: &str, namespace: &str, signature: &str, path: &Path) -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let allowed_signers = dir.path().join("allowed_signers");
+    let signature_path = dir.path().join("asset.sig");
+    tokio::fs::write(&allowed_signers, allowed_signers_line(public_key)).await?;
+    tokio::fs::write(&signature_path, signature).await?;
+
+    let data = std::fs::File::open(path)?;
+    let output = Command::new("ssh-keygen")
+        .arg("-Y")
+        .arg("verify")
+        .arg("-f")
+        .arg(&allowed_signers)
+        .arg("-I")
+        .arg(SSH_PRINCIPAL)
+        .arg("-n")
+        .arg(namespace)
+        .arg("-s")
+        .arg(&signature_path)
+        .stdin(Stdio::from(data))
+        .output()
+        .await
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                GrabError::NotFound("ssh-keygen is required to verify SSH signatures".to_string())
+            }
+            _ => GrabError::Io(e),
+        })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(GrabError::InvalidInput(stderr.trim().to_string()))
+    }
+}
+
+fn allowed_signers_line(public_key: &str) -> String {
+    format!("{SSH_PRINCIPAL} {}\n", public_key.trim())
+}
+
+pub fn signature_file_names(asset_name: &str, minisign: bool) -> Vec<String> {
+    if minisign {
+        vec![format!("{asset_name}{MINISIGN_SUFFIX}")]
+    } else {
+        SSH_SIGNATURE_SUFFIXES
+            .iter()
+            .map(|suffix| format!("{asset_name}{suffix}"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const PUBLIC_KEY: &str = "RWQHBgUEAwIBAAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+    const CONTENT: &[u8] = b"grab test asset\n";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQHBgUEAwIBANWUcP28ICxWx3CnZDySibK0WfZYdgcqBDiXA7ErzCF8pttY6AES9p8P4Uh+oV4eLTuXR+thwxcp/8VsPu37nwg=
+trusted comment: timestamp:1700000000\tfile:tool.tar.gz\thashed
+9j8Zpstgh/RRpU7bVaAdjniySfHlGyq926K2x0rJRJcLGqBS53We/j3UTJJ8vP71JxvGeK5IV51//6GJpoPoDA==";
+
+    const LEGACY_PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const LEGACY_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RWQf6LRCGA9i59SLOFxz6NxvASXDJeRtuZykwQepbDEGt87ig1BNpWaVWuNrm73YiIiJbq71Wi+dP9eKL8OC351vwIasSSbXxwA=
+trusted comment: timestamp:1555779966\tfile:test
+QtKMXWyYcwdpZAlPF7tE2ENJkRd1ujvKjlj1m9RtHTBnZPa5WKU5uWRs5GoP5M/VqE81QFuMKI5k/SfNQUaOAA==";
+
+    fn minisign(public_key: &str, signature: &str) -> Signature {
+        Signature::Minisign {
+            public_key: public_key.to_string(),
+            signature: signature.to_string(),
+        }
+    }
+
+    fn write_asset(content: &[u8]) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tool.tar.gz");
+        std::fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    #[tokio::test]
+    async fn test_minisign_valid_signature() {
+        let (_dir, path) = write_asset(CONTENT);
+        minisign(PUBLIC_KEY, SIGNATURE)
+            .verify(&path, "tool.tar.gz")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_minisign_legacy_signature() {
+        let (_dir, path) = write_asset(b"test");
+        minisign(LEGACY_PUBLIC_KEY, LEGACY_SIGNATURE)
+            .verify(&path, "test")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_minisign_tampered_file_fails() {
+        let (_dir, path) = write_asset(b"grab test asset, tampered\n");
+        let result = minisign(PUBLIC_KEY, SIGNATURE)
+            .verify(&path, "tool.tar.gz")
+            .await;
+        assert!(matches!(result, Err(GrabError::SignatureInvalid { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_minisign_wrong_key_fails() {
+        let (_dir, path) = write_asset(CONTENT);
+        let result = minisign(LEGACY_PUBLIC_KEY, SIGNATURE)
+            .verify(&path, "tool.tar.gz")
+            .await;
+        assert!(matches!(result, Err(GrabError::SignatureInvalid { .. })));
+    }
+
+    #[test]
+    fn test_validate_minisign_key() {
+        assert!(validate_minisign_key(PUBLIC_KEY).is_ok());
+        let file = format!("untrusted comment: minisign public key\n{PUBLIC_KEY}\n");
+        assert!(validate_minisign_key(&file).is_ok());
+        assert!(validate_minisign_key("not-a-key").is_err());
+    }
+
+    #[test]
+    fn test_validate_ssh_key() {
+        assert!(validate_ssh_key("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAA user@host").is_ok());
+        assert!(validate_ssh_key("ecdsa-sha2-nistp256 AAAAE2VjZHNh").is_ok());
+        assert!(validate_ssh_key("ssh-ed25519").is_err());
+        assert!(validate_ssh_key("RWQHBgUEAwIBAA").is_err());
+    }
+
+    #[test]
+    fn test_allowed_signers_line() {
+        assert_eq!(
+            allowed_signers_line(" ssh-ed25519 AAAA user@host\n"),
+            "grab ssh-ed25519 AAAA user@host\n"
+        );
+    }
+
+    #[test]
+    fn test_signature_file_names() {
+        assert_eq!(
+            signature_file_names("tool.tar.gz", true),
+            vec!["tool.tar.gz.minisig"]
+        );
+        assert_eq!(
+            signature_file_names("tool.tar.gz", false),
+            vec!["tool.tar.gz.sig", "tool.tar.gz.sshsig"]
+        );
+    }
+}
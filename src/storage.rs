@@ -3,9 +3,12 @@ use crate::models::App;
 use directories::UserDirs;
 use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 const JSON_FILE: &str = ".apps.json";
 
+static APPS_LOCK: Mutex<()> = Mutex::new(());
+
 fn get_json_path() -> Result<PathBuf> {
     if let Some(user_dirs) = UserDirs::new() {
         Ok(user_dirs.home_dir().join(JSON_FILE))
@@ -26,6 +29,11 @@ pub fn save_apps(apps: &[App]) -> Result<()> {
     save_apps_to(&path, apps)
 }
 
+pub fn modify_app(app: &App, change: impl FnOnce(&mut App)) -> Result<bool> {
+    let path = get_json_path()?;
+    modify_app_in(&path, app, change)
+}
+
 pub fn load_apps_from(path: &Path) -> Result<Vec<App>> {
     if !path.exists() {
         return Ok(Vec::new());
@@ -40,10 +48,27 @@ pub fn save_apps_to(path: &Path, apps: &[App]) -> Result<()> {
     serde_json::to_writer_pretty(file, apps).map_err(|e| GrabError::Parse(e.to_string()))
 }
 
+pub fn modify_app_in(path: &Path, app: &App, change: impl FnOnce(&mut App)) -> Result<bool> {
+    let _guard = APPS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut apps = load_apps_from(path)?;
+    let stored = apps
+        .iter_mut()
+        .find(|a| a.name == app.name && a.owner == app.owner && a.repo == app.repo);
+
+    match stored {
+        Some(stored) => {
+            change(stored);
+            save_apps_to(path, &apps)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::App;
+    use crate::models::{App, PatternKind};
     use tempfile::tempdir;
 
     fn make_app(name: &str, owner: &str, repo: &str) -> App {
@@ -52,7 +77,8 @@ mod tests {
             owner: owner.to_string(),
             repo: repo.to_string(),
             version_flag: "--version".to_string(),
-            asset_pattern: format!("{}-linux", name),
+            asset_pattern: Some(format!("{}-linux", name)),
+            ..Default::default()
         }
     }
 
@@ -145,6 +171,22 @@ mod tests {
         assert!(parsed.is_array());
     }
 
+    #[test]
+    fn test_load_entry_without_pattern_kind_defaults_to_substring() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("apps.json");
+
+        std::fs::write(
+            &path,
+            r#"[{"name":"fd","owner":"sharkdp","repo":"fd","asset_pattern":"linux","version_flag":"-V"}]"#,
+        )
+        .unwrap();
+
+        let loaded = load_apps_from(&path).unwrap();
+        assert_eq!(loaded[0].pattern_kind, PatternKind::Substring);
+        assert!(loaded[0].source.is_none());
+    }
+
     #[test]
     fn test_load_preserves_all_fields() {
         let dir = tempdir().unwrap();
@@ -155,7 +197,8 @@ mod tests {
             owner: "myorg".to_string(),
             repo: "mycli-repo".to_string(),
             version_flag: "-V".to_string(),
-            asset_pattern: "mycli-x86_64-linux".to_string(),
+            asset_pattern: Some("mycli-x86_64-linux".to_string()),
+            ..Default::default()
         };
 
         save_apps_to(&path, &[app]).unwrap();
@@ -165,6 +208,31 @@ mod tests {
         assert_eq!(loaded[0].owner, "myorg");
         assert_eq!(loaded[0].repo, "mycli-repo");
         assert_eq!(loaded[0].version_flag, "-V");
-        assert_eq!(loaded[0].asset_pattern, "mycli-x86_64-linux");
+        assert_eq!(
+            loaded[0].asset_pattern.as_deref(),
+            Some("mycli-x86_64-linux")
+        );
+    }
+
+    #[test]
+    fn test_modify_app_changes_only_matching_entry() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("apps.json");
+
+        let apps = vec![
+            make_app("ripgrep", "BurntSushi", "ripgrep"),
+            make_app("fd", "sharkdp", "fd"),
+        ];
+        save_apps_to(&path, &apps).unwrap();
+
+        let changed = modify_app_in(&path, &apps[0], |app| {
+            app.active_version = Some("14.1.0".to_string());
+        })
+        .unwrap();
+        assert!(changed);
+
+        let loaded = load_apps_from(&path).unwrap();
+        assert_eq!(loaded[0].active_version.as_deref(), Some("14.1.0"));
+        assert!(loaded[1].active_version.is_none());
     }
 }
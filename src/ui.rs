@@ -1,6 +1,9 @@
 use crate::error::Result;
 use crate::models::App;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use tokio::sync::{Mutex, MutexGuard};
+
+static PROMPT_LOCK: Mutex<()> = Mutex::const_new(());
 
 pub fn print_apps(apps: &[(usize, App)]) {
     if apps.is_empty() {
@@ -42,6 +45,74 @@ pub fn confirm_action(message: &str) -> Result<bool> {
     }
 }
 
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+pub async fn lock_prompts() -> MutexGuard<'static, ()> {
+    PROMPT_LOCK.lock().await
+}
+
+pub fn select_item(message: &str, items: &[String]) -> Result<usize> {
+    println!("{message}");
+    for (index, item) in items.iter().enumerate() {
+        println!("  [{}] {item}", index + 1);
+    }
+
+    loop {
+        let input = prompt_for_input(&format!("Choose 1-{}: ", items.len()), true)?;
+        match input.parse::<usize>() {
+            Ok(choice) if (1..=items.len()).contains(&choice) => return Ok(choice - 1),
+            _ => println!("Please enter a number between 1 and {}.", items.len()),
+        }
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+pub fn parse_size(value: &str) -> Option<u64> {
+    let trimmed = value.trim();
+    let trimmed = trimmed
+        .strip_suffix("iB")
+        .or_else(|| trimmed.strip_suffix('B'))
+        .unwrap_or(trimmed);
+
+    let (number, multiplier) = match trimmed.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&trimmed[..trimmed.len() - 1], 1024.0),
+        Some('M') => (&trimmed[..trimmed.len() - 1], 1024.0 * 1024.0),
+        Some('G') => (&trimmed[..trimmed.len() - 1], 1024.0 * 1024.0 * 1024.0),
+        _ => (trimmed, 1.0),
+    };
+
+    let number: f64 = number.trim().parse().ok()?;
+    let size = (number * multiplier).round();
+    (size.is_finite() && size >= 1.0).then_some(size as u64)
+}
+
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
 fn print_prompt(message: &str) -> Result<()> {
     print!("{message:}");
     Ok(io::stdout().flush()?)
@@ -129,6 +200,38 @@ mod tests {
         assert_eq!(parse_confirmation("abc123"), None);
     }
 
+    // --- format_size unit tests ---
+
+    #[test]
+    fn test_format_size_bytes() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+    }
+
+    #[test]
+    fn test_format_size_binary_units() {
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(200 * 1024 * 1024), "200.0 MiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_parse_size_round_trips_format_size_units() {
+        assert_eq!(parse_size("2G"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1.5 KiB"), Some(1536));
+        assert_eq!(parse_size("0"), None);
+        assert_eq!(parse_size("lots"), None);
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(120), "2m ago");
+        assert_eq!(format_age(7200), "2h ago");
+        assert_eq!(format_age(3 * 86400), "3d ago");
+    }
+
     // --- confirm_from_reader integration tests ---
 
     #[test]